@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use std::fmt::{self, Debug, Formatter};
 use std::hash::Hasher as StdHasher;
 use std::panic::panic_any;
@@ -19,7 +20,7 @@ use merkletree::{
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 
-use crate::types::{Domain, HashFunction, Hasher};
+use crate::types::{canonical_domain_from_bytes, Domain, HashFunction, Hasher};
 
 #[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
 pub struct Blake2sHasher {}
@@ -315,6 +316,14 @@ impl From<[u8; 32]> for Blake2sDomain {
     }
 }
 
+impl TryFrom<&[u8]> for Blake2sDomain {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: &[u8]) -> anyhow::Result<Self> {
+        canonical_domain_from_bytes(raw)
+    }
+}
+
 impl From<Blake2sDomain> for [u8; 32] {
     #[inline]
     fn from(val: Blake2sDomain) -> Self {
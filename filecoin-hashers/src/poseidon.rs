@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::hash::{Hash as StdHash, Hasher as StdHasher};
 use std::mem::size_of;
 use std::panic::panic_any;
@@ -21,8 +22,9 @@ use rand::RngCore;
 use serde::{Deserialize, Serialize};
 
 use crate::types::{
-    Domain, HashFunction, Hasher, PoseidonArity, PoseidonMDArity, POSEIDON_CONSTANTS_16,
-    POSEIDON_CONSTANTS_2, POSEIDON_CONSTANTS_4, POSEIDON_CONSTANTS_8, POSEIDON_MD_CONSTANTS,
+    canonical_domain_from_bytes, Domain, HashFunction, Hasher, PoseidonArity, PoseidonMDArity,
+    POSEIDON_CONSTANTS_16, POSEIDON_CONSTANTS_2, POSEIDON_CONSTANTS_4, POSEIDON_CONSTANTS_8,
+    POSEIDON_MD_CONSTANTS,
 };
 
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -392,6 +394,14 @@ impl From<FrRepr> for PoseidonDomain {
     }
 }
 
+impl TryFrom<&[u8]> for PoseidonDomain {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: &[u8]) -> anyhow::Result<Self> {
+        canonical_domain_from_bytes(raw)
+    }
+}
+
 impl From<PoseidonDomain> for Fr {
     #[inline]
     fn from(val: PoseidonDomain) -> Self {
@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use std::fmt::{self, Debug, Formatter};
 use std::hash::Hasher as StdHasher;
 use std::panic::panic_any;
@@ -17,7 +18,7 @@ use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
-use crate::types::{Domain, HashFunction, Hasher};
+use crate::types::{canonical_domain_from_bytes, Domain, HashFunction, Hasher};
 
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Sha256Hasher {}
@@ -331,6 +332,14 @@ impl From<[u8; 32]> for Sha256Domain {
     }
 }
 
+impl TryFrom<&[u8]> for Sha256Domain {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: &[u8]) -> anyhow::Result<Self> {
+        canonical_domain_from_bytes(raw)
+    }
+}
+
 impl From<Sha256Domain> for [u8; 32] {
     #[inline]
     fn from(val: Sha256Domain) -> Self {
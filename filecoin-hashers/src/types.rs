@@ -4,11 +4,13 @@ use std::hash::Hash as StdHash;
 #[cfg(feature = "poseidon")]
 pub use crate::poseidon_types::*;
 
+use anyhow::ensure;
 use bellperson::{
     bls::{Bls12, Fr, FrRepr},
     gadgets::{boolean::Boolean, num::AllocatedNum},
     ConstraintSystem, SynthesisError,
 };
+use ff::{PrimeField, PrimeFieldRepr};
 use merkletree::{
     hash::{Algorithm as LightAlgorithm, Hashable as LightHashable},
     merkle::Element,
@@ -122,3 +124,128 @@ pub trait Hasher: Clone + Debug + Eq + Default + Send + Sync {
 
     fn name() -> String;
 }
+
+/// Like `Domain::try_from_bytes`, but also fails unless `raw` is the canonical little-endian
+/// encoding of a field element. Backs the `TryFrom<&[u8]>` impls of the domains.
+pub(crate) fn canonical_domain_from_bytes<D: Domain>(raw: &[u8]) -> anyhow::Result<D> {
+    let domain = D::try_from_bytes(raw)?;
+    let mut repr = FrRepr::default();
+    repr.read_le(domain.as_ref())?;
+    ensure!(Fr::from_repr(repr).is_ok(), "non-canonical field element");
+
+    Ok(domain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+    use std::hash::Hasher as StdHasher;
+
+    use ff::Field;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn hash_of<D: Domain>(domain: &D) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        domain.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn domain_as_map_key<H: Hasher>() {
+        let rng = &mut XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut map = HashMap::new();
+        let frs: Vec<Fr> = (0..100).map(|_| Fr::random(rng)).collect();
+        for (i, fr) in frs.iter().enumerate() {
+            map.insert(H::Domain::from(*fr), i);
+        }
+        assert_eq!(map.len(), frs.len());
+
+        for (i, fr) in frs.iter().enumerate() {
+            // The same commitment, arriving through different conversions.
+            let from_fr = H::Domain::from(*fr);
+            let from_repr = H::Domain::from(fr.into_repr());
+            let from_bytes =
+                H::Domain::try_from_bytes(&from_fr.into_bytes()).expect("try_from_bytes failure");
+
+            for domain in &[from_repr, from_bytes] {
+                assert_eq!(*domain, from_fr);
+                assert_eq!(hash_of(domain), hash_of(&from_fr));
+                assert_eq!(map.get(domain), Some(&i));
+            }
+        }
+
+        map.insert(H::Domain::from(frs[0]), 0);
+        assert_eq!(map.len(), frs.len());
+    }
+
+    #[cfg(feature = "sha256")]
+    #[test]
+    fn test_sha256_domain_as_map_key() {
+        domain_as_map_key::<crate::sha256::Sha256Hasher>();
+    }
+
+    #[cfg(feature = "blake2s")]
+    #[test]
+    fn test_blake2s_domain_as_map_key() {
+        domain_as_map_key::<crate::blake2s::Blake2sHasher>();
+    }
+
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn test_poseidon_domain_as_map_key() {
+        domain_as_map_key::<crate::poseidon::PoseidonHasher>();
+    }
+
+    fn try_from_canonical_bytes<H: Hasher>()
+    where
+        H::Domain: for<'a> TryFrom<&'a [u8], Error = anyhow::Error>,
+    {
+        let rng = &mut XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+        let val = H::Domain::random(rng);
+        let bytes = val.into_bytes();
+        assert_eq!(
+            H::Domain::try_from(bytes.as_slice()).expect("try_from failure"),
+            val
+        );
+
+        assert!(H::Domain::try_from(&bytes[..31]).is_err());
+        assert!(H::Domain::try_from(&[bytes.as_slice(), &[0]].concat()[..]).is_err());
+
+        // The modulus and anything above it are not canonical.
+        let mut modulus = [0u8; 32];
+        Fr::char()
+            .write_le(&mut modulus[..])
+            .expect("write_le failure");
+        assert!(H::Domain::try_from(&modulus[..]).is_err());
+        assert!(H::Domain::try_from(&[0xff; 32][..]).is_err());
+    }
+
+    #[cfg(feature = "sha256")]
+    #[test]
+    fn test_sha256_try_from_canonical_bytes() {
+        try_from_canonical_bytes::<crate::sha256::Sha256Hasher>();
+    }
+
+    #[cfg(feature = "blake2s")]
+    #[test]
+    fn test_blake2s_try_from_canonical_bytes() {
+        try_from_canonical_bytes::<crate::blake2s::Blake2sHasher>();
+    }
+
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn test_poseidon_try_from_canonical_bytes() {
+        try_from_canonical_bytes::<crate::poseidon::PoseidonHasher>();
+    }
+}
@@ -1,5 +1,5 @@
 use std::fs::{self, metadata, File, OpenOptions};
-use std::io::Write;
+use std::io::{BufReader, Read, Write};
 use std::path::{Path, PathBuf};
 
 use anyhow::{ensure, Context, Result};
@@ -22,7 +22,7 @@ use storage_proofs_core::{
     parameter_cache::SRS_MAX_PROOFS_TO_AGGREGATE,
     proof::ProofScheme,
     sector::SectorId,
-    util::default_rows_to_discard,
+    util::{default_rows_to_discard, NODE_SIZE},
     Data,
 };
 use storage_proofs_porep::stacked::{
@@ -41,7 +41,7 @@ use crate::{
         SINGLE_PARTITION_PROOF_LEN,
     },
     parameters::setup_params,
-    pieces::{self, verify_pieces},
+    pieces::{self, piece_hash, verify_pieces},
     types::{
         AggregateSnarkProof, Commitment, PaddedBytesAmount, PieceInfo, PoRepConfig,
         PoRepProofPartitions, ProverId, SealCommitOutput, SealCommitPhase1Output,
@@ -901,6 +901,61 @@ pub fn compute_comm_d(sector_size: SectorSize, piece_infos: &[PieceInfo]) -> Res
     result
 }
 
+/// Computes the `comm_d` of the (already bit padded) sector data stored at `path`.
+///
+/// The data is streamed from disk and the tree is reduced as it is read, so only the roots of the
+/// complete subtrees seen so far are kept in memory, at most one per tree height.
+///
+/// # Arguments
+///
+/// * `path` - path to the sector data, its length must be a power of two of at least two nodes.
+pub fn comm_d_from_file<P: AsRef<Path>>(path: P) -> Result<Commitment> {
+    info!("comm_d_from_file:start");
+
+    let path = path.as_ref();
+    let file =
+        File::open(path).with_context(|| format!("could not open path={}", path.display()))?;
+    let len = file
+        .metadata()
+        .with_context(|| format!("could not read metadata of path={}", path.display()))?
+        .len();
+    ensure!(
+        len >= 2 * NODE_SIZE as u64 && len.is_power_of_two(),
+        "sector data length must be a power of two of at least {} bytes, got {}",
+        2 * NODE_SIZE,
+        len
+    );
+
+    let mut reader = BufReader::new(file);
+    let mut pair = [0u8; 2 * NODE_SIZE];
+    // Roots of the complete subtrees read so far, with their heights, strictly decreasing in
+    // height from the bottom of the stack.
+    let mut roots: Vec<(usize, DefaultPieceDomain)> = Vec::new();
+    for _ in 0..len / pair.len() as u64 {
+        reader
+            .read_exact(&mut pair)
+            .context("failed to read sector data")?;
+
+        let mut height = 0;
+        let mut root = piece_hash(&pair[..NODE_SIZE], &pair[NODE_SIZE..]);
+        while let Some(&(left_height, left)) = roots.last() {
+            if left_height != height {
+                break;
+            }
+            roots.pop();
+            height += 1;
+            root = piece_hash(left.as_ref(), root.as_ref());
+        }
+        roots.push((height, root));
+    }
+    ensure!(roots.len() == 1, "sector data did not form a single tree");
+
+    let comm_d = commitment_from_fr(roots[0].1.into());
+
+    info!("comm_d_from_file:finish");
+    Ok(comm_d)
+}
+
 /// Verifies the output of some previously-run seal operation.
 ///
 /// # Arguments
@@ -0,0 +1,123 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{ensure, Result};
+use filecoin_hashers::{
+    sha256::{Sha256Domain, Sha256Function, Sha256Hasher},
+    HashFunction, Hasher,
+};
+use storage_proofs_core::{
+    merkle::{BinaryMerkleTree, MerkleTreeTrait},
+    sector::SectorId,
+};
+
+use crate::types::Commitment;
+
+pub type CommitmentTreeProof = <BinaryMerkleTree<Sha256Hasher> as MerkleTreeTrait>::Proof;
+
+/// A merkle tree over the replica commitments of a set of sectors, so that the membership of a
+/// single sector can be proven against one root, without listing all sectors.
+#[derive(Debug)]
+pub struct CommitmentTree {
+    tree: BinaryMerkleTree<Sha256Hasher>,
+    /// Position of each sector's leaf in the tree.
+    indices: HashMap<SectorId, usize>,
+}
+
+impl CommitmentTree {
+    /// Builds the tree, leaves are ordered by sector id and padded with zero leaves to the next
+    /// power of two.
+    pub fn new(commitments: &BTreeMap<SectorId, Commitment>) -> Result<Self> {
+        ensure!(
+            !commitments.is_empty(),
+            "no commitments to build a tree from"
+        );
+
+        let leaf_count = commitments.len().next_power_of_two().max(2);
+        let mut leaves: Vec<Sha256Domain> = commitments
+            .iter()
+            .map(|(sector_id, comm_r)| Self::leaf(*sector_id, comm_r))
+            .collect();
+        leaves.resize(leaf_count, Sha256Domain::default());
+
+        let tree = BinaryMerkleTree::<Sha256Hasher>::new(leaves)?;
+        let indices = commitments
+            .keys()
+            .enumerate()
+            .map(|(index, sector_id)| (*sector_id, index))
+            .collect();
+
+        Ok(CommitmentTree { tree, indices })
+    }
+
+    /// The leaf committing to `comm_r` being the replica commitment of `sector_id`.
+    pub fn leaf(sector_id: SectorId, comm_r: &Commitment) -> <Sha256Hasher as Hasher>::Domain {
+        let mut data = [0u8; 40];
+        data[..8].copy_from_slice(&u64::from(sector_id).to_le_bytes());
+        data[8..].copy_from_slice(comm_r);
+
+        Sha256Function::hash(&data)
+    }
+
+    pub fn root(&self) -> Commitment {
+        self.tree.root().into()
+    }
+
+    /// The inclusion proof of the leaf of `sector_id`, or `None` if the sector is not part of the
+    /// tree.
+    pub fn inclusion_proof(&self, sector_id: SectorId) -> Result<Option<CommitmentTreeProof>> {
+        match self.indices.get(&sector_id) {
+            Some(index) => Ok(Some(self.tree.gen_proof(*index)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The number of sectors in the tree.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+    use storage_proofs_core::{merkle::MerkleProofTrait, TEST_SEED};
+
+    #[test]
+    fn test_commitment_tree() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+        let commitments: BTreeMap<SectorId, Commitment> = (0..1024u64)
+            .map(|i| (SectorId::from(i * 3), rng.gen()))
+            .collect();
+        let tree = CommitmentTree::new(&commitments).expect("failed to build tree");
+        assert_eq!(tree.len(), 1024);
+
+        for (sector_id, comm_r) in &commitments {
+            let proof = tree
+                .inclusion_proof(*sector_id)
+                .expect("failed to generate proof")
+                .expect("sector missing from tree");
+            assert!(proof.verify());
+            assert!(proof.validate_data(CommitmentTree::leaf(*sector_id, comm_r)));
+            assert_eq!(Commitment::from(proof.root()), tree.root());
+        }
+
+        assert!(tree
+            .inclusion_proof(SectorId::from(1))
+            .expect("failed to generate proof")
+            .is_none());
+
+        // Changing a single commitment changes the root.
+        let mut changed = commitments.clone();
+        changed.insert(SectorId::from(3), rng.gen());
+        let changed_tree = CommitmentTree::new(&changed).expect("failed to build tree");
+        assert_ne!(changed_tree.root(), tree.root());
+    }
+}
@@ -11,6 +11,7 @@ use storage_proofs_post::fallback;
 use crate::constants::DefaultPieceHasher;
 
 mod bytes_amount;
+mod commitment_tree;
 mod piece_info;
 mod porep_config;
 mod porep_proof_partitions;
@@ -22,6 +23,7 @@ mod sector_class;
 mod sector_size;
 
 pub use bytes_amount::*;
+pub use commitment_tree::*;
 pub use piece_info::*;
 pub use porep_config::*;
 pub use porep_proof_partitions::*;
@@ -1,10 +1,24 @@
 use fr32::to_unpadded_bytes;
+use storage_proofs_core::{drgraph::graph_height, util::NODE_SIZE};
+use typenum::U2;
 
 use crate::types::{PaddedBytesAmount, UnpaddedBytesAmount};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct SectorSize(pub u64);
 
+impl SectorSize {
+    /// Returns the number of `NODE_SIZE` leaves in a sector of this size.
+    pub fn leaves(self) -> u64 {
+        self.0 / NODE_SIZE as u64
+    }
+
+    /// Returns the depth of the binary merkle tree (`tree_d`) built over a sector of this size.
+    pub fn tree_depth(self) -> usize {
+        graph_height::<U2>(self.leaves() as usize) - 1
+    }
+}
+
 impl From<u64> for SectorSize {
     fn from(size: u64) -> Self {
         SectorSize(size)
@@ -28,3 +42,23 @@ impl From<SectorSize> for u64 {
         x.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::constants::{SECTOR_SIZE_2_KIB, SECTOR_SIZE_32_GIB, SECTOR_SIZE_512_MIB};
+
+    #[test]
+    fn sector_size_leaves_and_depth() {
+        let sector_size = SectorSize(SECTOR_SIZE_32_GIB);
+        assert_eq!(sector_size.leaves(), 1 << 30);
+        assert_eq!(sector_size.tree_depth(), 30);
+
+        assert_eq!(SectorSize(SECTOR_SIZE_512_MIB).leaves(), 1 << 24);
+        assert_eq!(SectorSize(SECTOR_SIZE_512_MIB).tree_depth(), 24);
+
+        assert_eq!(SectorSize(SECTOR_SIZE_2_KIB).leaves(), 64);
+        assert_eq!(SectorSize(SECTOR_SIZE_2_KIB).tree_depth(), 6);
+    }
+}
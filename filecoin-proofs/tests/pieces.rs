@@ -1,10 +1,11 @@
+use std::fs::File;
 use std::io::{Cursor, Read};
 use std::iter::Iterator;
 
 use anyhow::Result;
 use bellperson::bls::Fr;
 use filecoin_proofs::{
-    add_piece, commitment_from_fr,
+    add_piece, comm_d_from_file, commitment_from_fr,
     pieces::{
         compute_comm_d, get_piece_alignment, get_piece_start_byte, piece_hash, verify_pieces,
         zero_padding, EmptySource, PieceAlignment,
@@ -18,6 +19,7 @@ use storage_proofs_core::{
     api_version::ApiVersion, drgraph::Graph, merkle::create_base_merkle_tree, util::NODE_SIZE,
 };
 use storage_proofs_porep::stacked::StackedBucketGraph;
+use tempfile::NamedTempFile;
 
 #[test]
 fn test_empty_source() {
@@ -370,6 +372,53 @@ fn test_verify_random_pieces() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_comm_d_from_file() -> Result<()> {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+    let sector_size = SectorSize(16 * 1024);
+    let piece_size = UnpaddedBytesAmount::from(PaddedBytesAmount::from(sector_size));
+
+    let mut piece_bytes = vec![0u8; u64::from(piece_size) as usize];
+    rng.fill_bytes(&mut piece_bytes);
+
+    let staged_sector = NamedTempFile::new()?;
+    add_piece(
+        Cursor::new(&piece_bytes),
+        File::create(staged_sector.path())?,
+        piece_size,
+        &[],
+    )?;
+
+    let data = std::fs::read(staged_sector.path())?;
+    assert_eq!(data.len(), u64::from(sector_size) as usize);
+    let data_tree: DataTree =
+        create_base_merkle_tree::<DataTree>(None, data.len() / NODE_SIZE, &data)
+            .expect("failed to create data tree");
+    let expected = commitment_from_fr(data_tree.root().into());
+
+    assert_eq!(comm_d_from_file(staged_sector.path())?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_comm_d_from_file_invalid_length() -> Result<()> {
+    let empty = NamedTempFile::new()?;
+    assert!(comm_d_from_file(empty.path()).is_err());
+
+    // Three node pairs do not form a complete binary tree.
+    let non_power_of_two = NamedTempFile::new()?;
+    std::fs::write(non_power_of_two.path(), vec![0u8; 3 * 2 * NODE_SIZE])?;
+    assert!(comm_d_from_file(non_power_of_two.path()).is_err());
+
+    // A single node has no pair to hash.
+    let single_node = NamedTempFile::new()?;
+    std::fs::write(single_node.path(), vec![0u8; NODE_SIZE])?;
+    assert!(comm_d_from_file(single_node.path()).is_err());
+
+    Ok(())
+}
+
 fn build_sector(
     piece_sizes: &[UnpaddedBytesAmount],
     sector_size: SectorSize,
@@ -85,6 +85,15 @@ pub fn u64_into_fr(n: u64) -> Fr {
 mod tests {
     use super::*;
 
+    use ff::Field;
+
+    // r = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001, little-endian.
+    const MODULUS_BYTES: Fr32Ary = [
+        0x01, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0x02, 0xa4, 0xbd,
+        0x53, 0x05, 0xd8, 0xa1, 0x09, 0x08, 0xd8, 0x39, 0x33, 0x48, 0x7d, 0x9d, 0x29, 0x53, 0xa7,
+        0xed, 0x73,
+    ];
+
     fn bytes_fr_test(bytes: Fr32Ary, expect_success: bool) {
         let b = &bytes[..];
         let fr_result = bytes_into_fr(&b);
@@ -139,4 +148,31 @@ mod tests {
             false,
         );
     }
+
+    #[test]
+    fn test_field_modulus_and_byte_order() {
+        assert_eq!(Fr::NUM_BITS, 255);
+        assert_eq!(Fr::CAPACITY, 254);
+
+        // Frs are 32 bytes, little-endian.
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        assert_eq!(fr_into_bytes(&Fr::one()), one.to_vec());
+        assert_eq!(fr_into_bytes(&u64_into_fr(0x0102)), {
+            let mut bytes = [0u8; 32];
+            bytes[0] = 0x02;
+            bytes[1] = 0x01;
+            bytes.to_vec()
+        });
+
+        // The largest element is r - 1.
+        let mut minus_one = Fr::zero();
+        minus_one.sub_assign(&Fr::one());
+        let mut expected = MODULUS_BYTES;
+        expected[0] -= 1;
+        assert_eq!(fr_into_bytes(&minus_one), expected.to_vec());
+
+        bytes_fr_test(expected, true);
+        bytes_fr_test(MODULUS_BYTES, false);
+    }
 }
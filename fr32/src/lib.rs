@@ -1,7 +1,9 @@
 mod convert;
 mod padding;
 mod reader;
+mod validator;
 
 pub use convert::*;
 pub use padding::*;
 pub use reader::*;
+pub use validator::*;
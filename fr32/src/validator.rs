@@ -0,0 +1,95 @@
+/// Size in bytes of a padded Fr32 element.
+const ELEMENT_BYTES: usize = 32;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum PaddingError {
+    #[error("padded data length {0} is not a multiple of 32")]
+    BadLength(usize),
+    #[error("padding bit {bit} set in byte {byte_offset}")]
+    BadPaddingBit { byte_offset: usize, bit: u8 },
+}
+
+/// Checks that fr32 padded data conforms to the padding scheme before it is accepted for sealing,
+/// i.e. that the two most significant bits (254 and 255) of every 32-byte element are zero.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Fr32PaddingValidator;
+
+impl Fr32PaddingValidator {
+    pub fn new() -> Self {
+        Fr32PaddingValidator
+    }
+
+    /// Validates `chunk`, which must consist of whole elements. On the first violation, returns
+    /// the offset of the offending byte within `chunk` and the position of the set bit within its
+    /// element.
+    pub fn validate_chunk(&self, chunk: &[u8]) -> Result<(), PaddingError> {
+        if chunk.len() % ELEMENT_BYTES != 0 {
+            return Err(PaddingError::BadLength(chunk.len()));
+        }
+
+        for (i, element) in chunk.chunks(ELEMENT_BYTES).enumerate() {
+            // Elements are little-endian, the padding bits are the top bits of the last byte.
+            let last = element[ELEMENT_BYTES - 1];
+            for bit in 6..8 {
+                if last & (1 << bit) != 0 {
+                    return Err(PaddingError::BadPaddingBit {
+                        byte_offset: i * ELEMENT_BYTES + ELEMENT_BYTES - 1,
+                        bit: (8 * (ELEMENT_BYTES - 1) + bit) as u8,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::{Cursor, Read};
+
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    use crate::Fr32Reader;
+
+    const TEST_SEED: [u8; 16] = [
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ];
+
+    #[test]
+    fn test_validate_chunk() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+        let validator = Fr32PaddingValidator::new();
+
+        let data: Vec<u8> = (0..127 * 8).map(|_| rng.gen()).collect();
+        let mut padded = Vec::new();
+        Fr32Reader::new(Cursor::new(&data))
+            .read_to_end(&mut padded)
+            .expect("in-memory read failed");
+        assert_eq!(padded.len(), 1024);
+        assert_eq!(validator.validate_chunk(&padded), Ok(()));
+
+        for bit in &[254u8, 255] {
+            let mut bad = padded.clone();
+            bad[3 * 32 + 31] |= 1 << (bit - 248);
+            // Only the first violation is reported.
+            bad[5 * 32 + 31] |= 1 << 7;
+            assert_eq!(
+                validator.validate_chunk(&bad),
+                Err(PaddingError::BadPaddingBit {
+                    byte_offset: 3 * 32 + 31,
+                    bit: *bit,
+                })
+            );
+        }
+
+        assert_eq!(
+            validator.validate_chunk(&padded[..100]),
+            Err(PaddingError::BadLength(100))
+        );
+    }
+}
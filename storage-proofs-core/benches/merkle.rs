@@ -1,7 +1,11 @@
 use anyhow::Result;
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion,
+    Throughput,
+};
 use filecoin_hashers::{
-    poseidon::PoseidonDomain, poseidon::PoseidonHasher, sha256::Sha256Hasher, Domain,
+    blake2s::Blake2sHasher, poseidon::PoseidonDomain, poseidon::PoseidonHasher,
+    sha256::Sha256Hasher, Domain, Hasher,
 };
 use rand::{thread_rng, Rng};
 use storage_proofs_core::merkle::{create_base_merkle_tree, BinaryMerkleTree};
@@ -65,5 +69,48 @@ fn merkle_benchmark_poseidon(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, merkle_benchmark_sha256, merkle_benchmark_poseidon);
+fn bench_hasher<H: 'static + Hasher>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    name: &str,
+    n_nodes: usize,
+    data: &[u8],
+) {
+    group.bench_function(format!("{}-{}", name, n_nodes), |b| {
+        b.iter(|| {
+            black_box(create_base_merkle_tree::<BinaryMerkleTree<H>>(None, n_nodes, data).unwrap())
+        })
+    });
+}
+
+/// Builds the same tree with every hasher, reporting throughput so they can be compared directly.
+fn bench_hashers(c: &mut Criterion) {
+    let params = if cfg!(feature = "big-sector-sizes-bench") {
+        vec![1024, 1_048_576]
+    } else {
+        vec![1024]
+    };
+
+    let mut group = c.benchmark_group("merkletree-hashers");
+    for n_nodes in params {
+        // Valid field elements, so the same data can be used with every hasher.
+        let mut rng = thread_rng();
+        let data: Vec<u8> = (0..n_nodes)
+            .flat_map(|_| PoseidonDomain::random(&mut rng).into_bytes())
+            .collect();
+
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        bench_hasher::<Sha256Hasher>(&mut group, "sha256", n_nodes, &data);
+        bench_hasher::<Blake2sHasher>(&mut group, "blake2s", n_nodes, &data);
+        bench_hasher::<PoseidonHasher>(&mut group, "poseidon", n_nodes, &data);
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    merkle_benchmark_sha256,
+    merkle_benchmark_poseidon,
+    bench_hashers
+);
 criterion_main!(benches);
@@ -1,7 +1,10 @@
 use std::io::{Read, Seek, SeekFrom, Write};
 
+use bellperson::bls::Fr;
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ff::Field;
 use rand::{thread_rng, Rng};
+use storage_proofs_core::util::fr_batch_invert;
 use tempfile::tempfile;
 
 fn read_bytes_benchmark(c: &mut Criterion) {
@@ -30,5 +33,37 @@ fn read_bytes_benchmark(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, read_bytes_benchmark);
+fn batch_invert_benchmark(c: &mut Criterion) {
+    let params = vec![100, 10_000];
+
+    let mut group = c.benchmark_group("invert");
+    for n in params {
+        let mut rng = thread_rng();
+        let elements: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+
+        group.bench_function(format!("naive-{}", n), |b| {
+            b.iter(|| {
+                let inverses: Vec<Fr> = elements
+                    .iter()
+                    .map(|e| e.inverse().expect("inverse failure"))
+                    .collect();
+
+                black_box(inverses)
+            })
+        });
+
+        group.bench_function(format!("batch-{}", n), |b| {
+            b.iter(|| {
+                let mut inverses = elements.clone();
+                fr_batch_invert(&mut inverses);
+
+                black_box(inverses)
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, read_bytes_benchmark, batch_invert_benchmark);
 criterion_main!(benches);
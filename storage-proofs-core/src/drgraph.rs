@@ -1,5 +1,7 @@
 use std::cmp::{max, min};
+use std::collections::{BTreeSet, VecDeque};
 use std::fmt::Debug;
+use std::io::Write;
 use std::marker::PhantomData;
 
 use anyhow::ensure;
@@ -7,6 +9,7 @@ use filecoin_hashers::{Hasher, PoseidonArity};
 use fr32::bytes_into_fr_repr_safe;
 use generic_array::typenum::Unsigned;
 use merkletree::merkle::get_merkle_tree_row_count;
+use once_cell::sync::OnceCell;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use sha2::{Digest, Sha256};
@@ -92,6 +95,204 @@ pub struct BucketGraph<H: Hasher> {
     _h: PhantomData<H>,
 }
 
+/// The reverse adjacency of a `BucketGraph`, the children of every node. It is computed on first
+/// access and cached for the lifetime of this value.
+#[derive(Debug)]
+pub struct ReverseAdjacency<H: Hasher> {
+    graph: BucketGraph<H>,
+    children: OnceCell<Vec<Vec<usize>>>,
+}
+
+impl<H: Hasher> ReverseAdjacency<H> {
+    pub fn new(graph: BucketGraph<H>) -> Self {
+        ReverseAdjacency {
+            graph,
+            children: OnceCell::new(),
+        }
+    }
+
+    pub fn graph(&self) -> &BucketGraph<H> {
+        &self.graph
+    }
+
+    /// Returns, for every node, the nodes which have it as a parent, in increasing order. A child
+    /// is listed once, even if it references the same parent several times.
+    pub fn children(&self) -> &[Vec<usize>] {
+        self.children.get_or_init(|| {
+            let graph = &self.graph;
+            let mut children = vec![Vec::new(); graph.nodes];
+            let mut parents = vec![0; graph.degree()];
+
+            // Nodes 0 and 1 only reference the first node as a placeholder, they have no real
+            // parents apart from node 1 being a child of node 0.
+            if graph.nodes > 1 {
+                children[0].push(1);
+            }
+            for node in 2..graph.nodes {
+                graph
+                    .parents(node, &mut parents)
+                    .expect("parents are always in range");
+                // Parents may repeat, only record a single edge for each.
+                for parent in parents.iter().collect::<BTreeSet<_>>() {
+                    children[*parent as usize].push(node);
+                }
+            }
+
+            children
+        })
+    }
+}
+
+impl<H: Hasher> BucketGraph<H> {
+    /// Audits the constructed graph against the intended DRG parameters.
+    ///
+    /// Besides the degree, every node (except for the first two, which only reference the first
+    /// node) is checked to only have parents which precede it, including its immediate
+    /// predecessor. Nodes with repeated parents, i.e. fewer distinct parents than the degree, are
+    /// reported separately. Bucket graphs have no expander edges, so expecting any is a
+    /// discrepancy.
+    pub fn audit_spec(&self, expected_degree: usize, expected_expansion: usize) -> SpecReport {
+        let degree = self.degree();
+        let mut parents = vec![0; degree];
+        let mut invalid_nodes = Vec::new();
+        let mut duplicate_parent_nodes = Vec::new();
+
+        for node in 2..self.nodes {
+            if self.parents(node, &mut parents).is_err()
+                || !parents.iter().all(|parent| (*parent as usize) < node)
+                || !parents.contains(&(node as u32 - 1))
+            {
+                invalid_nodes.push(node);
+                continue;
+            }
+
+            if parents.iter().collect::<BTreeSet<_>>().len() < degree {
+                duplicate_parent_nodes.push(node);
+            }
+        }
+
+        SpecReport {
+            expected_degree,
+            degree,
+            expected_expansion,
+            duplicate_parent_nodes,
+            invalid_nodes,
+        }
+    }
+
+    /// Writes the first `max_nodes` nodes of the graph, with an edge from each parent to its
+    /// child, in Graphviz DOT format.
+    pub fn export_dot<W: Write>(&self, writer: &mut W, max_nodes: usize) -> Result<()> {
+        let nodes = min(self.nodes, max_nodes);
+        let mut parents = vec![0; self.degree()];
+
+        writeln!(writer, "digraph {{")?;
+        for node in 0..nodes {
+            writeln!(writer, "    {};", node)?;
+        }
+        if nodes > 1 {
+            writeln!(writer, "    0 -> 1;")?;
+        }
+        for node in 2..nodes {
+            self.parents(node, &mut parents)?;
+            // Parents may repeat, only draw a single edge for each.
+            for parent in parents.iter().collect::<BTreeSet<_>>() {
+                writeln!(writer, "    {} -> {};", parent, node)?;
+            }
+        }
+        writeln!(writer, "}}")?;
+
+        Ok(())
+    }
+
+    /// Returns the nodes in topological order, every node following all of its parents, using
+    /// Kahn's algorithm.
+    pub fn topological_sort(&self) -> Vec<usize> {
+        let reverse_adjacency = ReverseAdjacency::new(self.clone());
+        let children = reverse_adjacency.children();
+
+        // The in-degree of a node is its number of distinct parents.
+        let mut in_degree = vec![0usize; self.nodes];
+        for node_children in children {
+            for child in node_children {
+                in_degree[*child] += 1;
+            }
+        }
+
+        let mut ready: VecDeque<usize> = (0..self.nodes)
+            .filter(|node| in_degree[*node] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes);
+        while let Some(node) = ready.pop_front() {
+            order.push(node);
+            for child in &children[node] {
+                in_degree[*child] -= 1;
+                if in_degree[*child] == 0 {
+                    ready.push_back(*child);
+                }
+            }
+        }
+
+        debug_assert_eq!(order.len(), self.nodes, "graph has a cycle");
+        debug_assert!(Self::is_topological_order(children, &order));
+
+        order
+    }
+
+    /// Returns true if every node in `order` appears after all of its parents.
+    fn is_topological_order(children: &[Vec<usize>], order: &[usize]) -> bool {
+        let mut position = vec![0; children.len()];
+        for (i, node) in order.iter().enumerate() {
+            position[*node] = i;
+        }
+
+        children.iter().enumerate().all(|(parent, children)| {
+            children
+                .iter()
+                .all(|child| position[parent] < position[*child])
+        })
+    }
+
+    /// Returns a graph with `1 / factor` of the nodes, sampled with the same degree, seed and api
+    /// version, so tests can exercise the same code paths on a smaller graph.
+    pub fn downsample(&self, factor: usize) -> Result<Self> {
+        ensure!(factor > 0, "downsample factor must be positive");
+        let nodes = self.nodes / factor;
+        ensure!(nodes >= 2, "downsampled graph needs at least two nodes");
+
+        Ok(BucketGraph {
+            nodes,
+            base_degree: self.base_degree,
+            seed: self.seed,
+            api_version: self.api_version,
+            _h: PhantomData,
+        })
+    }
+}
+
+/// The result of `BucketGraph::audit_spec`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecReport {
+    pub expected_degree: usize,
+    /// The number of parents generated per node.
+    pub degree: usize,
+    pub expected_expansion: usize,
+    /// Nodes with fewer distinct parents than the degree. Bucket sampling may pick the same
+    /// parent more than once, so these are not discrepancies by themselves.
+    pub duplicate_parent_nodes: Vec<usize>,
+    /// Nodes whose parents do not match the spec.
+    pub invalid_nodes: Vec<usize>,
+}
+
+impl SpecReport {
+    /// Returns true if no discrepancies were found.
+    pub fn is_clean(&self) -> bool {
+        self.degree == self.expected_degree
+            && self.expected_expansion == 0
+            && self.invalid_nodes.is_empty()
+    }
+}
+
 impl<H: Hasher> ParameterSetMetadata for BucketGraph<H> {
     fn identifier(&self) -> String {
         // NOTE: Seed is not included because it does not influence parameter generation.
@@ -349,6 +550,130 @@ mod tests {
         }
     }
 
+    #[test]
+    fn reverse_adjacency_is_cached() {
+        let g = BucketGraph::<Sha256Hasher>::new(256, BASE_DEGREE, 0, [1; 32], ApiVersion::V1_1_0)
+            .expect("bucket graph new failed");
+        let reverse_adjacency = ReverseAdjacency::new(g);
+
+        let first = reverse_adjacency.children();
+        let second = reverse_adjacency.children();
+        assert!(
+            std::ptr::eq(first, second),
+            "reverse adjacency was recomputed"
+        );
+
+        let mut parents = vec![0; BASE_DEGREE];
+        for node in 2..g.size() {
+            g.parents(node, &mut parents).expect("parents failed");
+            for parent in &parents {
+                assert!(first[*parent as usize].contains(&node));
+            }
+        }
+        assert_eq!(first[0][0], 1);
+
+        // Every child is listed once, even when it repeats the parent.
+        for children in first {
+            assert!(children.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn audit_spec() {
+        let g = BucketGraph::<Sha256Hasher>::new(256, BASE_DEGREE, 0, [1; 32], ApiVersion::V1_1_0)
+            .expect("bucket graph new failed");
+        let report = g.audit_spec(BASE_DEGREE, 0);
+        assert!(report.is_clean(), "{:?}", report);
+
+        // Nodes with repeated parents are the ones drawing an edge fewer than the degree.
+        let mut parents = vec![0; BASE_DEGREE];
+        let expected_duplicates: Vec<usize> = (2..256)
+            .filter(|node| {
+                g.parents(*node, &mut parents).expect("parents failed");
+                parents.iter().collect::<BTreeSet<_>>().len() < BASE_DEGREE
+            })
+            .collect();
+        assert!(!expected_duplicates.is_empty());
+        assert_eq!(report.duplicate_parent_nodes, expected_duplicates);
+
+        // Node 2 can only pick from nodes 0 and 1.
+        assert_eq!(report.duplicate_parent_nodes[0], 2);
+
+        // Expecting expander edges from a bucket graph is a discrepancy.
+        let report = g.audit_spec(BASE_DEGREE, 8);
+        assert!(!report.is_clean());
+        assert!(report.invalid_nodes.is_empty());
+
+        // A graph built with the wrong degree is flagged.
+        let misbuilt =
+            BucketGraph::<Sha256Hasher>::new(256, BASE_DEGREE - 2, 0, [1; 32], ApiVersion::V1_1_0)
+                .expect("bucket graph new failed");
+        let report = misbuilt.audit_spec(BASE_DEGREE, 0);
+        assert!(!report.is_clean());
+        assert_eq!(report.degree, BASE_DEGREE - 2);
+    }
+
+    #[test]
+    fn downsample() {
+        let g =
+            BucketGraph::<Sha256Hasher>::new(1 << 20, BASE_DEGREE, 0, [1; 32], ApiVersion::V1_1_0)
+                .expect("bucket graph new failed");
+
+        let small = g.downsample(1 << 10).expect("downsample failed");
+        assert_eq!(small.size(), 1 << 10);
+        assert_eq!(small.degree(), g.degree());
+        assert_eq!(small.seed(), g.seed());
+
+        // All parents precede their node, so the graph is acyclic.
+        let report = small.audit_spec(BASE_DEGREE, 0);
+        assert!(report.is_clean(), "{:?}", report);
+
+        assert!(g.downsample(0).is_err());
+        assert!(g.downsample(1 << 20).is_err());
+    }
+
+    #[test]
+    fn topological_sort() {
+        let g = BucketGraph::<Sha256Hasher>::new(1024, BASE_DEGREE, 0, [1; 32], ApiVersion::V1_1_0)
+            .expect("bucket graph new failed");
+
+        let order = g.topological_sort();
+        let reverse_adjacency = ReverseAdjacency::new(g);
+        let children = reverse_adjacency.children();
+        assert!(BucketGraph::<Sha256Hasher>::is_topological_order(
+            children, &order
+        ));
+
+        // Every node has its immediate predecessor as a parent, so the order is unique.
+        assert_eq!(order, (0..1024).collect::<Vec<_>>());
+
+        // Any other order puts some node before one of its parents.
+        let mut swapped = order;
+        swapped.swap(10, 11);
+        assert!(!BucketGraph::<Sha256Hasher>::is_topological_order(
+            children, &swapped
+        ));
+    }
+
+    #[test]
+    fn export_dot() {
+        let g = BucketGraph::<Sha256Hasher>::new(256, BASE_DEGREE, 0, [1; 32], ApiVersion::V1_1_0)
+            .expect("bucket graph new failed");
+
+        let mut out = Vec::new();
+        g.export_dot(&mut out, 16).expect("export_dot failed");
+        let dot = String::from_utf8(out).expect("invalid utf8");
+
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.trim_end().ends_with('}'));
+        assert!(dot.contains("    15;"));
+        assert!(!dot.contains("    16;"));
+        // Every node has an edge from its immediate predecessor.
+        for node in 1..16 {
+            assert!(dot.contains(&format!("    {} -> {};", node - 1, node)));
+        }
+    }
+
     #[test]
     fn graph_bucket_sha256() {
         graph_bucket::<Sha256Hasher>();
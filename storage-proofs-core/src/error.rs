@@ -48,3 +48,33 @@ impl From<Box<dyn Any + Send>> for Error {
         Error::Unclassified(format!("{:?}", dbg!(inner)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error::Error as StdError;
+    use std::fs::File;
+    use std::io;
+
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_io_error_source() {
+        let dir = tempdir().expect("tempdir failure");
+        let err = File::open(dir.path().join("missing"))
+            .map_err(Error::from)
+            .expect_err("opened a missing file");
+
+        assert!(matches!(err, Error::Io(_)));
+        let source = err
+            .source()
+            .and_then(|source| source.downcast_ref::<io::Error>())
+            .expect("source is not an io::Error");
+        assert_eq!(source.kind(), io::ErrorKind::NotFound);
+
+        // The chain is preserved when the error is passed on as an `anyhow::Error`.
+        let err = anyhow::Error::from(err);
+        assert!(err.chain().any(|cause| cause.is::<io::Error>()));
+    }
+}
@@ -1,4 +1,6 @@
-use bellperson::{bls::Engine, gadgets::num::AllocatedNum, ConstraintSystem, SynthesisError};
+use bellperson::{
+    bls::Engine, gadgets::num::AllocatedNum, ConstraintSystem, LinearCombination, SynthesisError,
+};
 use ff::Field;
 
 /// Adds a constraint to CS, enforcing an equality relationship between the allocated numbers a and b.
@@ -80,6 +82,120 @@ pub fn sub<E: Engine, CS: ConstraintSystem<E>>(
     Ok(res)
 }
 
+/// Allocates the constant `value`, which does not need to be supplied as a witness.
+///
+/// value_num = value
+pub fn alloc_const<E: Engine, CS: ConstraintSystem<E>>(
+    mut cs: CS,
+    value: E::Fr,
+) -> Result<AllocatedNum<E>, SynthesisError> {
+    let num = AllocatedNum::alloc(cs.namespace(|| "const_num"), || Ok(value))?;
+
+    // value_num * 1 = value
+    cs.enforce(
+        || "const constraint",
+        |lc| lc + num.get_variable(),
+        |lc| lc + CS::one(),
+        |lc| lc + (value, CS::one()),
+    );
+
+    Ok(num)
+}
+
+/// Adds a constraint to CS, enforcing that the allocated number val equals the constant.
+///
+/// val == constant
+pub fn assert_equal_to_constant<E: Engine, CS: ConstraintSystem<E>>(
+    mut cs: CS,
+    val: &AllocatedNum<E>,
+    constant: E::Fr,
+) -> Result<(), SynthesisError> {
+    // (val - constant) * 1 = 0
+    cs.enforce(
+        || "equal to constant",
+        |lc| lc + val.get_variable() - (constant, CS::one()),
+        |lc| lc + CS::one(),
+        |lc| lc,
+    );
+
+    Ok(())
+}
+
+/// Adds constraints to CS, enforcing that the allocated number val is one of the `allowed` values,
+/// as the product of the differences to all of them is zero. Costs `max(1, K - 1)` constraints for
+/// `K` allowed values, and fails with `Unsatisfiable` if there are none.
+///
+/// (val - allowed[0]) * (val - allowed[1]) * ... = 0
+pub fn assert_in_set<E: Engine, CS: ConstraintSystem<E>>(
+    mut cs: CS,
+    val: &AllocatedNum<E>,
+    allowed: &[E::Fr],
+) -> Result<(), SynthesisError> {
+    let (first, rest) = allowed.split_first().ok_or(SynthesisError::Unsatisfiable)?;
+
+    let diff = |a: &E::Fr| {
+        val.get_value().map(|mut diff| {
+            diff.sub_assign(a);
+            diff
+        })
+    };
+
+    if rest.is_empty() {
+        return assert_equal_to_constant(cs, val, *first);
+    }
+
+    let mut product_lc = LinearCombination::zero() + val.get_variable() - (*first, CS::one());
+    let mut product_value = diff(first);
+    for (i, a) in rest.iter().enumerate() {
+        let value = product_value.and_then(|mut product| {
+            diff(a).map(|diff| {
+                product.mul_assign(&diff);
+                product
+            })
+        });
+
+        if i == rest.len() - 1 {
+            // product * (val - allowed[last]) = 0
+            cs.enforce(
+                || "in set",
+                |_| product_lc.clone(),
+                |lc| lc + val.get_variable() - (*a, CS::one()),
+                |lc| lc,
+            );
+        } else {
+            let product = AllocatedNum::alloc(cs.namespace(|| format!("product_{}", i)), || {
+                value.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+
+            // product_{i - 1} * (val - allowed[i]) = product_i
+            cs.enforce(
+                || format!("product_{} constraint", i),
+                |_| product_lc.clone(),
+                |lc| lc + val.get_variable() - (*a, CS::one()),
+                |lc| lc + product.get_variable(),
+            );
+            product_lc = LinearCombination::zero() + product.get_variable();
+        }
+        product_value = value;
+    }
+
+    Ok(())
+}
+
+/// Exposes `num` as a public input, but only if `enabled` is set. Useful to inputize
+/// intermediate values while debugging a circuit.
+pub fn conditional_inputize<E: Engine, CS: ConstraintSystem<E>>(
+    cs: CS,
+    num: &AllocatedNum<E>,
+    enabled: bool,
+) -> Result<(), SynthesisError> {
+    if enabled {
+        num.inputize(cs)?;
+    }
+
+    Ok(())
+}
+
 /// Adds a constraint to CS, enforcing a difference relationship between the allocated numbers a, b, and difference.
 ///
 /// a - b = difference
@@ -160,4 +276,105 @@ mod tests {
             assert!(cs.is_satisfied());
         }
     }
+
+    #[test]
+    fn alloc_const_constraint() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+        for _ in 0..100 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let value = Fr::random(rng);
+            let num = alloc_const(cs.namespace(|| "const"), value).expect("alloc_const failed");
+
+            assert_eq!(num.get_value().expect("get_value failed"), value);
+            assert_eq!(cs.num_constraints(), 1);
+            assert!(cs.is_satisfied());
+
+            // Any other assignment violates the constraint.
+            cs.set("const/const_num/num", Fr::random(rng));
+            assert!(!cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn assert_equal_to_constant_constraint() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+        for constant in &[Fr::zero(), Fr::one(), Fr::random(rng)] {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let num = AllocatedNum::alloc(cs.namespace(|| "num"), || Ok(*constant))
+                .expect("alloc failed");
+            assert_equal_to_constant(cs.namespace(|| "assert"), &num, *constant)
+                .expect("assert_equal_to_constant failed");
+
+            assert_eq!(cs.num_constraints(), 1);
+            assert!(cs.is_satisfied());
+
+            let mut other = *constant;
+            other.add_assign(&Fr::one());
+            cs.set("num/num", other);
+            assert!(!cs.is_satisfied());
+            assert_eq!(cs.which_is_unsatisfied(), Some("assert/equal to constant"));
+        }
+    }
+
+    #[test]
+    fn assert_in_set_constraint() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+        for k in 1..6 {
+            let allowed: Vec<Fr> = (0..k).map(|_| Fr::random(rng)).collect();
+
+            for value in &allowed {
+                let mut cs = TestConstraintSystem::<Bls12>::new();
+                let num = AllocatedNum::alloc(cs.namespace(|| "num"), || Ok(*value))
+                    .expect("alloc failed");
+                assert_in_set(cs.namespace(|| "set"), &num, &allowed)
+                    .expect("assert_in_set failed");
+
+                assert!(cs.is_satisfied());
+                assert_eq!(cs.num_constraints(), std::cmp::max(1, k - 1));
+            }
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            let num = AllocatedNum::alloc(cs.namespace(|| "num"), || Ok(Fr::random(rng)))
+                .expect("alloc failed");
+            assert_in_set(cs.namespace(|| "set"), &num, &allowed).expect("assert_in_set failed");
+            assert!(!cs.is_satisfied());
+        }
+
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+        let num =
+            AllocatedNum::alloc(cs.namespace(|| "num"), || Ok(Fr::one())).expect("alloc failed");
+        assert!(assert_in_set(cs.namespace(|| "set"), &num, &[]).is_err());
+    }
+
+    #[test]
+    fn conditional_inputize_constraint() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+        for enabled in &[true, false] {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let value = Fr::random(rng);
+            let num =
+                AllocatedNum::alloc(cs.namespace(|| "num"), || Ok(value)).expect("alloc failed");
+            conditional_inputize(cs.namespace(|| "inputize"), &num, *enabled)
+                .expect("conditional_inputize failed");
+
+            assert!(cs.is_satisfied());
+            if *enabled {
+                // ONE and the inputized value.
+                assert_eq!(cs.num_inputs(), 2);
+                assert_eq!(cs.num_constraints(), 1);
+                assert!(cs.verify(&[value]));
+            } else {
+                assert_eq!(cs.num_inputs(), 1);
+                assert_eq!(cs.num_constraints(), 0);
+                assert!(cs.verify(&[]));
+            }
+        }
+    }
 }
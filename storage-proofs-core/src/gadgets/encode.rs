@@ -2,6 +2,7 @@ use bellperson::{bls::Engine, gadgets::num::AllocatedNum, ConstraintSystem, Synt
 
 use crate::gadgets::constraint;
 
+/// Circuit version of `crypto::sloth::encode`: allocates `value + key`.
 pub fn encode<E, CS>(
     mut cs: CS,
     key: &AllocatedNum<E>,
@@ -14,6 +15,8 @@ where
     constraint::add(cs.namespace(|| "encode_add"), key, value)
 }
 
+/// Circuit version of `crypto::sloth::decode`: allocates `value - key`, undoing `encode` under
+/// the same `key`.
 pub fn decode<E, CS>(
     mut cs: CS,
     key: &AllocatedNum<E>,
@@ -25,3 +28,51 @@ where
 {
     constraint::sub(cs.namespace(|| "decode_sub"), value, key)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bellperson::{
+        bls::{Bls12, Fr},
+        util_cs::test_cs::TestConstraintSystem,
+    };
+    use ff::Field;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    use crate::{crypto::sloth, TEST_SEED};
+
+    #[test]
+    fn decode_circuit_matches_vanilla() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+        for i in 0..50 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let key_fr = Fr::random(rng);
+            let y_fr = Fr::random(rng);
+
+            let key =
+                AllocatedNum::alloc(cs.namespace(|| "key"), || Ok(key_fr)).expect("alloc failed");
+            let y = AllocatedNum::alloc(cs.namespace(|| "y"), || Ok(y_fr)).expect("alloc failed");
+
+            let decoded = decode(cs.namespace(|| "decode"), &key, &y).expect("decode failed");
+            let encoded = encode(cs.namespace(|| "encode"), &key, &decoded).expect("encode failed");
+
+            assert!(cs.is_satisfied(), "constraints not satisfied");
+            assert_eq!(
+                decoded.get_value().expect("get_value failed"),
+                sloth::decode(&key_fr, &y_fr),
+                "circuit and vanilla decode do not match for pair {}",
+                i
+            );
+            assert_eq!(
+                encoded.get_value().expect("get_value failed"),
+                y_fr,
+                "encode does not invert decode for pair {}",
+                i
+            );
+        }
+    }
+}
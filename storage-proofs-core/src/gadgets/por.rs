@@ -33,12 +33,14 @@ use crate::{
 /// * `value` - The value of the leaf.
 /// * `auth_path` - The authentication path of the leaf in the tree.
 /// * `root` - The merkle root of the tree.
+/// * `allowed_values` - If set, the leaf must also be one of these public values.
 ///
 pub struct PoRCircuit<Tree: MerkleTreeTrait> {
     value: Root<Bls12>,
     auth_path: AuthPath<Tree::Hasher, Tree::Arity, Tree::SubTreeArity, Tree::TopTreeArity>,
     root: Root<Bls12>,
     private: bool,
+    allowed_values: Option<Vec<Fr>>,
     _tree: PhantomData<Tree>,
 }
 
@@ -292,6 +294,7 @@ impl<'a, Tree: 'static + MerkleTreeTrait> CompoundProof<'a, PoR<Tree>, PoRCircui
             auth_path: proof.proof.as_options().into(),
             root,
             private,
+            allowed_values: None,
             _tree: PhantomData,
         })
     }
@@ -304,6 +307,7 @@ impl<'a, Tree: 'static + MerkleTreeTrait> CompoundProof<'a, PoR<Tree>, PoRCircui
             auth_path: AuthPath::blank(public_params.leaves),
             root: Root::Val(None),
             private: public_params.private,
+            allowed_values: None,
             _tree: PhantomData,
         }
     }
@@ -382,6 +386,13 @@ impl<'a, Tree: MerkleTreeTrait> Circuit<Bls12> for PoRCircuit<Tree> {
 
         {
             let value_num = value.allocated(cs.namespace(|| "value"))?;
+            if let Some(allowed_values) = &self.allowed_values {
+                constraint::assert_in_set(
+                    cs.namespace(|| "allowed_values"),
+                    &value_num,
+                    allowed_values,
+                )?;
+            }
             let cur = value_num;
 
             // Ascend the merkle tree authentication path
@@ -427,10 +438,18 @@ impl<'a, Tree: MerkleTreeTrait> PoRCircuit<Tree> {
             auth_path: proof.as_options().into(),
             root: Root::Val(Some(proof.root().into())),
             private,
+            allowed_values: None,
             _tree: PhantomData,
         }
     }
 
+    /// Additionally requires the leaf to equal one of `allowed_values`, at a cost of
+    /// `max(1, K - 1)` constraints for `K` values.
+    pub fn with_allowed_values(mut self, allowed_values: Vec<Fr>) -> Self {
+        self.allowed_values = Some(allowed_values);
+        self
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn synthesize<CS>(
         mut cs: CS,
@@ -447,6 +466,7 @@ impl<'a, Tree: MerkleTreeTrait> PoRCircuit<Tree> {
             auth_path,
             root,
             private,
+            allowed_values: None,
             _tree: PhantomData,
         };
 
@@ -737,6 +757,7 @@ mod tests {
                         .into(),
                 )),
                 private: false,
+                allowed_values: None,
                 _tree: PhantomData,
             };
 
@@ -988,6 +1009,7 @@ mod tests {
                 auth_path: proof.proof.as_options().into(),
                 root: Root::Val(Some(tree.root().into())),
                 private: true,
+                allowed_values: None,
                 _tree: PhantomData,
             };
 
@@ -1020,4 +1042,65 @@ mod tests {
             assert!(cs.verify(&expected_inputs), "failed to verify inputs");
         }
     }
+
+    #[test]
+    fn test_por_circuit_allowed_values() {
+        type Tree = TestTree<PoseidonHasher, U8>;
+
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+        let leaves = 64;
+        let challenge = 3;
+        let data: Vec<u8> = (0..leaves)
+            .flat_map(|_| fr_into_bytes(&Fr::random(rng)))
+            .collect();
+        let tree = create_base_merkle_tree::<Tree>(None, leaves, data.as_slice())
+            .expect("create_base_merkle_tree failure");
+        let leaf = bytes_into_fr(data_at_node(&data, challenge).expect("data_at_node failure"))
+            .expect("bytes_into_fr failure");
+        let others: Vec<Fr> = (0..3).map(|_| Fr::random(rng)).collect();
+
+        let synthesize = |allowed_values: Option<Vec<Fr>>| {
+            let proof = tree.gen_proof(challenge).expect("gen_proof failure");
+            let mut por = PoRCircuit::<Tree>::new(proof, false);
+            if let Some(allowed_values) = allowed_values {
+                por = por.with_allowed_values(allowed_values);
+            }
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            por.synthesize(&mut cs).expect("circuit synthesis failed");
+            cs
+        };
+
+        let unrestricted = synthesize(None);
+        assert!(unrestricted.is_satisfied(), "constraints not satisfied");
+
+        // The leaf is in the set.
+        let cs = synthesize(Some(vec![others[0], leaf, others[1]]));
+        assert!(cs.is_satisfied(), "constraints not satisfied");
+        assert_eq!(cs.num_constraints(), unrestricted.num_constraints() + 2);
+        assert_eq!(cs.num_inputs(), unrestricted.num_inputs());
+
+        let cs = synthesize(Some(vec![leaf]));
+        assert!(cs.is_satisfied(), "constraints not satisfied");
+        assert_eq!(cs.num_constraints(), unrestricted.num_constraints() + 1);
+
+        // The leaf is not in the set.
+        let cs = synthesize(Some(others.clone()));
+        assert!(
+            !cs.is_satisfied(),
+            "leaf outside the set satisfied constraints"
+        );
+        assert_eq!(cs.which_is_unsatisfied(), Some("allowed_values/in set"));
+
+        let cs = synthesize(Some(vec![others[2]]));
+        assert!(
+            !cs.is_satisfied(),
+            "leaf outside the set satisfied constraints"
+        );
+        assert_eq!(
+            cs.which_is_unsatisfied(),
+            Some("allowed_values/equal to constant")
+        );
+    }
 }
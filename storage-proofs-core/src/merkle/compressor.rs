@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use anyhow::ensure;
+use filecoin_hashers::{Hasher, PoseidonArity};
+
+use crate::{
+    error::Result,
+    merkle::{MerkleProof, MerkleProofTrait},
+};
+
+/// A batch of merkle proofs for the same tree, where sibling hashes shared between the paths are
+/// only stored once.
+#[derive(Debug, Clone)]
+pub struct CompressedBatch<
+    H: Hasher,
+    BaseArity: PoseidonArity,
+    SubTreeArity: PoseidonArity,
+    TopTreeArity: PoseidonArity,
+> {
+    /// Proof which the decompressed proofs are shaped after, also carries the shared root.
+    template: MerkleProof<H, BaseArity, SubTreeArity, TopTreeArity>,
+    /// The distinct sibling hashes, per level of the path.
+    levels: Vec<Vec<Vec<H::Domain>>>,
+    /// The leaf and, per level, the position in `levels` and the path index, for every proof.
+    entries: Vec<(H::Domain, Vec<(usize, usize)>)>,
+}
+
+impl<
+        H: Hasher,
+        BaseArity: PoseidonArity,
+        SubTreeArity: PoseidonArity,
+        TopTreeArity: PoseidonArity,
+    > CompressedBatch<H, BaseArity, SubTreeArity, TopTreeArity>
+{
+    /// The number of proofs in the batch.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of sibling hashes stored in the batch.
+    pub fn stored_hashes(&self) -> usize {
+        self.levels
+            .iter()
+            .flat_map(|level| level.iter())
+            .map(|hashes| hashes.len())
+            .sum()
+    }
+}
+
+/// Deduplicates the sibling hashes shared by merkle paths into the same tree, which happens when
+/// challenges land on nearby leaves.
+#[derive(Debug, Clone, Copy)]
+pub struct MerklePathCompressor;
+
+impl MerklePathCompressor {
+    /// Compresses `paths`, which all must be proofs for the same tree.
+    pub fn compress<
+        H: 'static + Hasher,
+        BaseArity: 'static + PoseidonArity,
+        SubTreeArity: 'static + PoseidonArity,
+        TopTreeArity: 'static + PoseidonArity,
+    >(
+        paths: &[MerkleProof<H, BaseArity, SubTreeArity, TopTreeArity>],
+    ) -> Result<CompressedBatch<H, BaseArity, SubTreeArity, TopTreeArity>> {
+        ensure!(!paths.is_empty(), "no paths to compress");
+        let template = paths[0].clone();
+        let path_len = template.len();
+
+        let mut levels: Vec<Vec<Vec<H::Domain>>> = vec![Vec::new(); path_len];
+        let mut positions: Vec<HashMap<Vec<H::Domain>, usize>> = vec![HashMap::new(); path_len];
+        let mut entries = Vec::with_capacity(paths.len());
+
+        for proof in paths {
+            ensure!(
+                proof.root() == template.root(),
+                "paths have different roots"
+            );
+            ensure!(proof.len() == path_len, "paths have different lengths");
+
+            let elements = proof
+                .path()
+                .into_iter()
+                .enumerate()
+                .map(|(level, (hashes, index))| {
+                    let position = *positions[level].entry(hashes).or_insert_with_key(|hashes| {
+                        levels[level].push(hashes.clone());
+                        levels[level].len() - 1
+                    });
+                    (position, index)
+                })
+                .collect();
+
+            entries.push((proof.leaf(), elements));
+        }
+
+        Ok(CompressedBatch {
+            template,
+            levels,
+            entries,
+        })
+    }
+
+    /// Restores the proof at `index` from the `batch`.
+    pub fn decompress<
+        H: 'static + Hasher,
+        BaseArity: 'static + PoseidonArity,
+        SubTreeArity: 'static + PoseidonArity,
+        TopTreeArity: 'static + PoseidonArity,
+    >(
+        batch: &CompressedBatch<H, BaseArity, SubTreeArity, TopTreeArity>,
+        index: usize,
+    ) -> Result<MerkleProof<H, BaseArity, SubTreeArity, TopTreeArity>> {
+        ensure!(index < batch.len(), "index out of bounds: {}", index);
+        let (leaf, elements) = &batch.entries[index];
+
+        let path = elements
+            .iter()
+            .enumerate()
+            .map(|(level, (position, path_index))| {
+                (batch.levels[level][*position].clone(), *path_index)
+            })
+            .collect();
+
+        Ok(batch.template.with_leaf_and_path(*leaf, path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use filecoin_hashers::sha256::Sha256Hasher;
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    use crate::{
+        merkle::{generate_tree, BinaryMerkleTree, MerkleTreeTrait},
+        TEST_SEED,
+    };
+
+    #[test]
+    fn test_compress_decompress() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+        let nodes = 1 << 20;
+        let (_data, tree) = generate_tree::<BinaryMerkleTree<Sha256Hasher>, _>(rng, nodes, None);
+
+        let proofs: Vec<_> = (0..10)
+            .map(|_| {
+                tree.gen_proof(rng.gen_range(0, nodes))
+                    .expect("gen_proof failure")
+            })
+            .collect();
+
+        let batch = MerklePathCompressor::compress(&proofs).expect("compress failure");
+        assert_eq!(batch.len(), proofs.len());
+
+        for (i, proof) in proofs.iter().enumerate() {
+            let decompressed =
+                MerklePathCompressor::decompress(&batch, i).expect("decompress failure");
+            assert!(decompressed.verify());
+            assert_eq!(decompressed.leaf(), proof.leaf());
+            assert_eq!(decompressed.root(), proof.root());
+            assert_eq!(decompressed.path(), proof.path());
+        }
+
+        // Paths always share the siblings close to the root.
+        let uncompressed: usize = proofs
+            .iter()
+            .map(|proof| {
+                proof
+                    .path()
+                    .iter()
+                    .map(|(hashes, _)| hashes.len())
+                    .sum::<usize>()
+            })
+            .sum();
+        assert!(
+            batch.stored_hashes() < uncompressed,
+            "no hashes were deduplicated ({} stored)",
+            batch.stored_hashes()
+        );
+    }
+}
@@ -9,10 +9,12 @@ use generic_array::typenum::{U0, U2, U4, U8};
 use merkletree::store::LevelCacheStore;
 
 mod builders;
+mod compressor;
 mod proof;
 mod tree;
 
 pub use builders::*;
+pub use compressor::*;
 pub use proof::*;
 pub use tree::*;
 
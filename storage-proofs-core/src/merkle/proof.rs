@@ -212,6 +212,15 @@ impl<H: Hasher, Arity: PoseidonArity> InclusionPath<H, Arity> {
             .rev()
             .fold(0, |acc, p| (acc * Arity::to_usize()) + p.index)
     }
+
+    /// Replaces the hashes and indices of all elements, in order, with the ones taken from `elements`.
+    fn replace_elements<I: Iterator<Item = (Vec<H::Domain>, usize)>>(&mut self, elements: &mut I) {
+        for element in self.path.iter_mut() {
+            let (hashes, index) = elements.next().expect("not enough path elements");
+            element.hashes = hashes;
+            element.index = index;
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -473,6 +482,45 @@ impl<
     }
 }
 
+impl<
+        H: Hasher,
+        BaseArity: PoseidonArity,
+        SubTreeArity: PoseidonArity,
+        TopTreeArity: PoseidonArity,
+    > MerkleProof<H, BaseArity, SubTreeArity, TopTreeArity>
+{
+    /// Returns a proof of the same shape and root as `self`, with the given leaf and path, where
+    /// `path` is laid out as returned by `MerkleProofTrait::path`.
+    pub(crate) fn with_leaf_and_path(
+        &self,
+        leaf: H::Domain,
+        path: Vec<(Vec<H::Domain>, usize)>,
+    ) -> Self {
+        let mut proof = self.clone();
+        let mut elements = path.into_iter();
+        match proof.data {
+            ProofData::Single(ref mut p) => {
+                p.leaf = leaf;
+                p.path.replace_elements(&mut elements);
+            }
+            ProofData::Sub(ref mut p) => {
+                p.leaf = leaf;
+                p.base_proof.replace_elements(&mut elements);
+                p.sub_proof.replace_elements(&mut elements);
+            }
+            ProofData::Top(ref mut p) => {
+                p.leaf = leaf;
+                p.base_proof.replace_elements(&mut elements);
+                p.sub_proof.replace_elements(&mut elements);
+                p.top_proof.replace_elements(&mut elements);
+            }
+        }
+        assert!(elements.next().is_none(), "too many path elements");
+
+        proof
+    }
+}
+
 /// Converts a merkle_light proof to a SingleProof
 fn proof_to_single<H: Hasher, Arity: PoseidonArity, TargetArity: PoseidonArity>(
     proof: &merkletree::proof::Proof<H::Domain, Arity>,
@@ -706,7 +754,8 @@ mod tests {
     use rand::thread_rng;
 
     use crate::merkle::{
-        generate_tree, get_base_tree_count, DiskStore, MerkleTreeTrait, MerkleTreeWrapper,
+        create_base_merkle_tree, generate_tree, get_base_tree_count, BinaryMerkleTree, DiskStore,
+        MerkleTreeTrait, MerkleTreeWrapper,
     };
 
     fn merklepath<Tree: 'static + MerkleTreeTrait>() {
@@ -733,6 +782,37 @@ mod tests {
         }
     }
 
+    fn binary_root<H: 'static + Hasher>(data: &[u8]) -> Vec<u8> {
+        let nodes = data.len() / 32;
+        let tree = create_base_merkle_tree::<BinaryMerkleTree<H>>(None, nodes, data)
+            .expect("create_base_merkle_tree failure");
+
+        for i in 0..nodes {
+            assert!(tree.gen_proof(i).expect("gen_proof failure").verify());
+        }
+
+        tree.root().into_bytes()
+    }
+
+    #[test]
+    fn hashers_build_distinct_roots() {
+        let mut rng = thread_rng();
+        let data: Vec<u8> = (0..64)
+            .flat_map(|_| <PoseidonHasher as Hasher>::Domain::random(&mut rng).into_bytes())
+            .collect();
+
+        let roots = [
+            binary_root::<Sha256Hasher>(&data),
+            binary_root::<Blake2sHasher>(&data),
+            binary_root::<PoseidonHasher>(&data),
+        ];
+        for i in 0..roots.len() {
+            for j in i + 1..roots.len() {
+                assert_ne!(roots[i], roots[j], "hashers {} and {} agree", i, j);
+            }
+        }
+    }
+
     #[test]
     fn merklepath_poseidon_2() {
         merklepath::<
@@ -13,7 +13,8 @@ pub struct MultiProof<'a> {
     pub verifying_key: &'a PreparedVerifyingKey<Bls12>,
 }
 
-const GROTH_PROOF_SIZE: usize = 192;
+/// The length in bytes of a serialized Groth16 proof over BLS12-381.
+pub const GROTH_PROOF_SIZE: usize = 192;
 
 impl<'a> MultiProof<'a> {
     pub fn new(
@@ -76,7 +77,79 @@ impl<'a> MultiProof<'a> {
         self.circuit_proofs.len()
     }
 
+    /// Returns the length in bytes of the serialized proofs, as written by `write`.
+    pub fn serialized_len(&self) -> usize {
+        self.len() * GROTH_PROOF_SIZE
+    }
+
     pub fn is_empty(&self) -> bool {
         self.circuit_proofs.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bellperson::{
+        bls::{Engine, Fr},
+        groth16::{create_random_proof, generate_random_parameters, prepare_verifying_key},
+        Circuit, ConstraintSystem, SynthesisError,
+    };
+    use ff::Field;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    use crate::TEST_SEED;
+
+    /// Proves knowledge of a square root.
+    struct SquareRoot {
+        root: Option<Fr>,
+    }
+
+    impl<E: Engine<Fr = Fr>> Circuit<E> for SquareRoot {
+        fn synthesize<CS: ConstraintSystem<E>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+            let root = self.root;
+            let root_var = cs.alloc(|| "root", || root.ok_or(SynthesisError::AssignmentMissing))?;
+            let square_var = cs.alloc_input(
+                || "square",
+                || {
+                    let mut square = root.ok_or(SynthesisError::AssignmentMissing)?;
+                    square.square();
+                    Ok(square)
+                },
+            )?;
+            cs.enforce(
+                || "root * root = square",
+                |lc| lc + root_var,
+                |lc| lc + root_var,
+                |lc| lc + square_var,
+            );
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_serialized_len() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+        let params = generate_random_parameters::<Bls12, _, _>(SquareRoot { root: None }, rng)
+            .expect("failed to generate parameters");
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let proofs = (0..3)
+            .map(|_| {
+                let circuit = SquareRoot {
+                    root: Some(Fr::random(rng)),
+                };
+                create_random_proof(circuit, &params, rng).expect("failed to create proof")
+            })
+            .collect();
+        let multi_proof = MultiProof::new(proofs, &pvk);
+
+        let bytes = multi_proof.to_vec().expect("failed to serialize");
+        assert_eq!(multi_proof.serialized_len(), bytes.len());
+        assert_eq!(multi_proof.serialized_len(), 3 * GROTH_PROOF_SIZE);
+    }
+}
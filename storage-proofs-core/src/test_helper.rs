@@ -1,9 +1,14 @@
-use std::fs::OpenOptions;
-use std::io::Write;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
 use std::path::Path;
 
+use anyhow::Context;
+use bellperson::{bls::Engine, util_cs::test_cs::TestConstraintSystem};
+use ff::PrimeField;
 use memmap::{MmapMut, MmapOptions};
 
+use crate::error::Result;
+
 pub fn setup_replica(data: &[u8], replica_path: &Path) -> MmapMut {
     let mut f = OpenOptions::new()
         .read(true)
@@ -20,6 +25,38 @@ pub fn setup_replica(data: &[u8], replica_path: &Path) -> MmapMut {
     }
 }
 
+/// Writes all variable assignments of a constraint system to a file, one `KIND name = value` line
+/// per variable, with values in big endian hex. Useful to diff witnesses of failing proofs.
+pub trait DumpAssignments {
+    fn dump_assignments<P: AsRef<Path>>(&mut self, path: P) -> Result<()>;
+}
+
+impl<E: Engine> DumpAssignments for TestConstraintSystem<E> {
+    fn dump_assignments<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("could not create path={}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+
+        for (value, name) in self.get_inputs() {
+            writeln!(writer, "INPUT {} = {}", name, value.into_repr())?;
+        }
+
+        let aux_names: Vec<String> = self
+            .pretty_print_list()
+            .into_iter()
+            .filter_map(|line| line.strip_prefix("AUX ").map(ToString::to_string))
+            .collect();
+        for name in aux_names {
+            let value = self.get(&name);
+            writeln!(writer, "AUX {} = {}", name, value.into_repr())?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
 #[macro_export]
 macro_rules! table_tests {
     ($property_test_func:ident {
@@ -34,3 +71,46 @@ macro_rules! table_tests {
         )+
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    use bellperson::{
+        bls::{Bls12, Fr},
+        gadgets::num::AllocatedNum,
+        ConstraintSystem,
+    };
+    use ff::Field;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    use tempfile::tempdir;
+
+    use crate::{gadgets::encode, TEST_SEED};
+
+    #[test]
+    fn test_dump_assignments() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+        let key = Fr::random(rng);
+        let value = Fr::random(rng);
+
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+        let key_num =
+            AllocatedNum::alloc(cs.namespace(|| "key"), || Ok(key)).expect("alloc failure");
+        let value_num =
+            AllocatedNum::alloc(cs.namespace(|| "value"), || Ok(value)).expect("alloc failure");
+        encode::encode(cs.namespace(|| "encode"), &key_num, &value_num).expect("encode failure");
+        assert!(cs.is_satisfied());
+
+        let dir = tempdir().expect("tempdir failure");
+        let path = dir.path().join("assignments.txt");
+        cs.dump_assignments(&path).expect("dump failure");
+
+        let dump = fs::read_to_string(&path).expect("read failure");
+        assert!(dump.contains(&format!("INPUT ONE = {}", Fr::one().into_repr())));
+        assert!(dump.contains(&format!("AUX key/num = {}", key.into_repr())));
+        assert!(dump.contains(&format!("AUX value/num = {}", value.into_repr())));
+    }
+}
@@ -2,10 +2,11 @@ use std::cmp::min;
 
 use anyhow::ensure;
 use bellperson::{
-    bls::Engine,
+    bls::{Engine, Fr},
     gadgets::boolean::{AllocatedBit, Boolean},
     ConstraintSystem, SynthesisError,
 };
+use ff::Field;
 use merkletree::merkle::get_merkle_tree_row_count;
 
 use crate::{error::Error, settings::SETTINGS};
@@ -178,6 +179,34 @@ pub fn default_rows_to_discard(leafs: usize, arity: usize) -> usize {
     }
 }
 
+/// Inverts all `elements` in place, using a single field inversion and `3 * n` multiplications for
+/// `n` non-zero elements (Montgomery's trick). Zero elements have no inverse and are left unchanged.
+pub fn fr_batch_invert(elements: &mut [Fr]) {
+    // The products of all non-zero elements preceding each non-zero element.
+    let mut prefixes = Vec::with_capacity(elements.len());
+    let mut acc = Fr::one();
+    for element in elements.iter().filter(|e| !e.is_zero()) {
+        prefixes.push(acc);
+        acc.mul_assign(element);
+    }
+
+    let mut inv = acc
+        .inverse()
+        .expect("product of non-zero elements is non-zero");
+    for (element, prefix) in elements
+        .iter_mut()
+        .rev()
+        .filter(|e| !e.is_zero())
+        .zip(prefixes.into_iter().rev())
+    {
+        // `inv` is the inverse of the product of all elements up to and including `element`.
+        let mut element_inv = inv;
+        element_inv.mul_assign(&prefix);
+        inv.mul_assign(element);
+        *element = element_inv;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,4 +364,27 @@ mod tests {
             "circuit and non circuit do not match"
         );
     }
+
+    #[test]
+    fn test_fr_batch_invert() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+        let mut empty: Vec<Fr> = Vec::new();
+        fr_batch_invert(&mut empty);
+
+        for n in &[1, 2, 10, 100] {
+            let mut elements: Vec<Fr> = (0..*n).map(|_| Fr::random(rng)).collect();
+            // Zeros must be skipped.
+            elements[n / 2] = Fr::zero();
+            elements[n - 1] = Fr::zero();
+
+            let expected: Vec<Fr> = elements
+                .iter()
+                .map(|e| e.inverse().unwrap_or_else(Fr::zero))
+                .collect();
+
+            fr_batch_invert(&mut elements);
+            assert_eq!(elements, expected);
+        }
+    }
 }
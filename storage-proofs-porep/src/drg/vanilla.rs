@@ -1,3 +1,4 @@
+use std::io::Write;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 
@@ -464,15 +465,7 @@ where
 
         let mut parents = vec![0; graph.degree()];
         for node in 0..graph.size() {
-            graph.parents(node, &mut parents)?;
-            let key = graph.create_key(replica_id, node, &parents, data.as_ref(), None)?;
-            let start = data_at_node_offset(node);
-            let end = start + NODE_SIZE;
-
-            let node_data = <H as Hasher>::Domain::try_from_bytes(&data.as_ref()[start..end])?;
-            let encoded: H::Domain = sloth_encode::<H>(key.as_ref(), &node_data);
-
-            encoded.write_bytes(&mut data.as_mut()[start..end])?;
+            encode_node::<H, G>(graph, replica_id, data.as_mut(), node, &mut parents)?;
         }
 
         let replica_config = ReplicaConfig {
@@ -521,6 +514,60 @@ where
     }
 }
 
+/// Encodes a single node of `data` in place.
+///
+/// All parents of `node` must already be encoded, which is the case when nodes are encoded in
+/// order. `parents` is scratch space of `graph.degree()` elements, so that callers encoding many
+/// nodes can allocate it once.
+pub fn encode_node<H, G>(
+    graph: &G,
+    replica_id: &<H as Hasher>::Domain,
+    data: &mut [u8],
+    node: usize,
+    parents: &mut [u32],
+) -> Result<()>
+where
+    H: Hasher,
+    G::Key: AsRef<H::Domain>,
+    G: Graph<H>,
+{
+    graph.parents(node, parents)?;
+    let key = graph.create_key(replica_id, node, parents, data, None)?;
+    let start = data_at_node_offset(node);
+    let end = start + NODE_SIZE;
+
+    let node_data = <H as Hasher>::Domain::try_from_bytes(&data[start..end])?;
+    let encoded: H::Domain = sloth_encode::<H>(key.as_ref(), &node_data);
+
+    encoded.write_bytes(&mut data[start..end])
+}
+
+/// Verifies the encoding of a single node of the `replica` against the `original_chunk`.
+///
+/// Only `node` and its parents need to be encoded already, so this can be used to check each
+/// node right after it has been sealed.
+pub fn verify_node<H, G>(
+    replica: &[u8],
+    graph: &G,
+    replica_id: &<H as Hasher>::Domain,
+    node: usize,
+    original_chunk: &[u8],
+) -> Result<bool>
+where
+    H: Hasher,
+    G::Key: AsRef<H::Domain>,
+    G: Graph<H>,
+{
+    ensure!(
+        original_chunk.len() == NODE_SIZE,
+        "invalid chunk length: {}",
+        original_chunk.len()
+    );
+    let decoded = decode_block::<H, G>(graph, replica_id, replica, None, node)?;
+
+    Ok(AsRef::<[u8]>::as_ref(&decoded) == original_chunk)
+}
+
 pub fn decode<'a, H, G>(
     graph: &'a G,
     replica_id: &'a <H as Hasher>::Domain,
@@ -546,6 +593,36 @@ where
     Ok(())
 }
 
+/// Decodes the `replica` and writes the recovered data to `writer`, one node at a time.
+///
+/// Unlike `decode`, the replica is left untouched and no buffer of the size of the replica is
+/// allocated.
+pub fn extract_all_to_writer<H, G, W>(
+    replica: &[u8],
+    graph: &G,
+    replica_id: &<H as Hasher>::Domain,
+    writer: &mut W,
+) -> Result<()>
+where
+    H: Hasher,
+    G::Key: AsRef<H::Domain>,
+    G: Graph<H>,
+    W: Write,
+{
+    ensure!(
+        replica.len() == graph.size() * NODE_SIZE,
+        "invalid replica length: {}",
+        replica.len()
+    );
+
+    for node in 0..graph.size() {
+        let decoded = decode_block::<H, G>(graph, replica_id, replica, None, node)?;
+        writer.write_all(AsRef::<[u8]>::as_ref(&decoded))?;
+    }
+
+    Ok(())
+}
+
 pub fn decode_block<'a, H, G>(
     graph: &'a G,
     replica_id: &'a <H as Hasher>::Domain,
@@ -0,0 +1,154 @@
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+
+use anyhow::{ensure, Context};
+use mapr::{MmapMut, MmapOptions};
+use storage_proofs_core::{cache_key::CacheKey, error::Result, util::NODE_SIZE};
+
+/// Memory mapped, file backed storage of the labels of a single SDR layer.
+///
+/// This is a standalone building block: the labeling in `create_label` keeps writing its layers
+/// to the `DiskStore`s that tree building reads from, and does not go through this cache.
+#[derive(Debug)]
+pub struct LabelCache {
+    /// Disk path for the cache.
+    pub path: PathBuf,
+    /// The layer these labels belong to.
+    layer: usize,
+    /// The number of labels in the layer.
+    node_count: usize,
+    data: MmapMut,
+}
+
+impl LabelCache {
+    /// Creates (or reopens) the label cache for `layer` in `cache_dir`, sized to hold
+    /// `node_count` labels. Fails if `node_count` is zero, as an empty file cannot be mapped, or if
+    /// an existing cache was created for a different `node_count`.
+    pub fn new(layer: usize, node_count: usize, cache_dir: &Path) -> Result<Self> {
+        ensure!(node_count > 0, "label cache must hold at least one label");
+
+        let path = cache_dir.join(format!("{}-label-cache.dat", CacheKey::label_layer(layer)));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("could not open path={}", path.display()))?;
+
+        let cache_size = (node_count * NODE_SIZE) as u64;
+        let existing_size = file
+            .metadata()
+            .with_context(|| format!("could not read metadata of path={}", path.display()))?
+            .len();
+        ensure!(
+            existing_size == 0 || existing_size == cache_size,
+            "label cache at {} holds {} bytes, expected {}",
+            path.display(),
+            existing_size,
+            cache_size
+        );
+        file.set_len(cache_size)
+            .with_context(|| format!("failed to set length: {}", cache_size))?;
+
+        let data = unsafe {
+            MmapOptions::new()
+                .map_mut(&file)
+                .with_context(|| format!("could not mmap path={}", path.display()))?
+        };
+
+        Ok(LabelCache {
+            path,
+            layer,
+            node_count,
+            data,
+        })
+    }
+
+    pub fn layer(&self) -> usize {
+        self.layer
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// Stores the label of the given node.
+    ///
+    /// Panics if the `node` is not in the layer.
+    pub fn write_label(&mut self, node: usize, label: [u8; NODE_SIZE]) {
+        assert!(node < self.node_count, "node not in cache");
+        let start = node * NODE_SIZE;
+        self.data[start..start + NODE_SIZE].copy_from_slice(&label);
+    }
+
+    /// Reads the label of the given node.
+    ///
+    /// Panics if the `node` is not in the layer.
+    pub fn read_label(&self, node: usize) -> [u8; NODE_SIZE] {
+        assert!(node < self.node_count, "node not in cache");
+        let start = node * NODE_SIZE;
+        let mut label = [0u8; NODE_SIZE];
+        label.copy_from_slice(&self.data[start..start + NODE_SIZE]);
+        label
+    }
+
+    /// Flushes all written labels to disk.
+    pub fn flush(&self) -> Result<()> {
+        self.data.flush().context("failed to flush label cache")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+    use storage_proofs_core::TEST_SEED;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_label_cache_roundtrip() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+        let cache_dir = tempdir().expect("tempdir failure");
+        let node_count = 64;
+        let labels: Vec<[u8; NODE_SIZE]> = (0..node_count).map(|_| rng.gen()).collect();
+
+        {
+            let mut cache =
+                LabelCache::new(1, node_count, cache_dir.path()).expect("label cache failure");
+            for (node, label) in labels.iter().enumerate() {
+                cache.write_label(node, *label);
+            }
+            for (node, label) in labels.iter().enumerate() {
+                assert_eq!(&cache.read_label(node), label);
+            }
+            cache.flush().expect("flush failure");
+        }
+
+        // Labels survive reopening the cache from disk.
+        let cache = LabelCache::new(1, node_count, cache_dir.path()).expect("label cache failure");
+        assert_eq!(cache.layer(), 1);
+        for (node, label) in labels.iter().enumerate() {
+            assert_eq!(&cache.read_label(node), label);
+        }
+    }
+
+    #[test]
+    fn test_label_cache_empty_layer() {
+        let cache_dir = tempdir().expect("tempdir failure");
+        assert!(LabelCache::new(1, 0, cache_dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_label_cache_reopen_with_other_node_count() {
+        let cache_dir = tempdir().expect("tempdir failure");
+        LabelCache::new(1, 64, cache_dir.path()).expect("label cache failure");
+
+        assert!(LabelCache::new(1, 32, cache_dir.path()).is_err());
+        assert!(LabelCache::new(1, 128, cache_dir.path()).is_err());
+        LabelCache::new(1, 64, cache_dir.path()).expect("label cache failure");
+    }
+}
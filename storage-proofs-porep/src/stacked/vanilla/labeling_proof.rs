@@ -56,3 +56,44 @@ impl<H: Hasher> LabelingProof<H> {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use filecoin_hashers::{sha256::Sha256Hasher, Domain};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    use storage_proofs_core::TEST_SEED;
+
+    use crate::stacked::TOTAL_PARENTS;
+
+    #[test]
+    fn test_verify_parent_bit_flip() {
+        let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+        let replica_id = <Sha256Hasher as Hasher>::Domain::random(rng);
+        let parents: Vec<_> = (0..TOTAL_PARENTS)
+            .map(|_| <Sha256Hasher as Hasher>::Domain::random(rng))
+            .collect();
+
+        let proof = LabelingProof::<Sha256Hasher>::new(3, 1234, parents.clone());
+        let label = proof.create_label(&replica_id);
+        assert!(proof.verify(&replica_id, &label));
+
+        for i in 0..parents.len() {
+            for bit in 0..256 {
+                let mut corrupted = parents.clone();
+                corrupted[i].0[bit / 8] ^= 1 << (bit % 8);
+
+                let proof = LabelingProof::<Sha256Hasher>::new(3, 1234, corrupted);
+                assert!(
+                    !proof.verify(&replica_id, &label),
+                    "flipping bit {} of parent {} went undetected",
+                    bit,
+                    i
+                );
+            }
+        }
+    }
+}
@@ -12,6 +12,7 @@ mod column_proof;
 mod cores;
 mod encoding_proof;
 mod graph;
+mod label_cache;
 mod labeling_proof;
 #[cfg(feature = "multicore-sdr")]
 mod memory_handling;
@@ -27,6 +28,7 @@ pub use column::Column;
 pub use column_proof::ColumnProof;
 pub use encoding_proof::EncodingProof;
 pub use graph::{StackedBucketGraph, StackedGraph, EXP_DEGREE};
+pub use label_cache::LabelCache;
 pub use labeling_proof::LabelingProof;
 pub use params::*;
 pub use proof::{StackedDrg, TOTAL_PARENTS};
@@ -205,6 +205,123 @@ fn test_drg_porep_circuit() {
     cache_dir.close().expect("Failed to remove cache dir");
 }
 
+#[test]
+fn test_drg_porep_circuit_corrupted_data_node() {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    let nodes = 16;
+    let challenge = 2;
+
+    let replica_id: Fr = Fr::random(rng);
+
+    let data: Vec<u8> = (0..nodes)
+        .flat_map(|_| fr_into_bytes(&Fr::random(rng)))
+        .collect();
+
+    let cache_dir = tempdir().expect("tempdir failure");
+    let config = StoreConfig::new(
+        cache_dir.path(),
+        CacheKey::CommDTree.to_string(),
+        default_rows_to_discard(nodes, BINARY_ARITY),
+    );
+    let replica_path = cache_dir.path().join("replica-path");
+    let mut mmapped_data = setup_replica(&data, &replica_path);
+
+    let data_node = bytes_into_fr(
+        data_at_node(&mmapped_data, challenge).expect("failed to read original data"),
+    )
+    .expect("bytes_into_fr failure");
+
+    let sp = drg::SetupParams {
+        drg: drg::DrgParams {
+            nodes,
+            degree: BASE_DEGREE,
+            expansion_degree: 0,
+            porep_id: [32; 32],
+        },
+        private: false,
+        challenges_count: 1,
+        api_version: ApiVersion::V1_1_0,
+    };
+
+    let pp = DrgPoRep::<PoseidonHasher, BucketGraph<_>>::setup(&sp)
+        .expect("failed to create drgporep setup");
+    let (tau, aux) = DrgPoRep::<PoseidonHasher, _>::replicate(
+        &pp,
+        &replica_id.into(),
+        (mmapped_data.as_mut()).into(),
+        None,
+        config,
+        replica_path,
+    )
+    .expect("failed to replicate");
+
+    let pub_inputs = drg::PublicInputs {
+        replica_id: Some(replica_id.into()),
+        challenges: vec![challenge],
+        tau: Some(tau),
+    };
+    let priv_inputs = drg::PrivateInputs::<PoseidonHasher> {
+        tree_d: &aux.tree_d,
+        tree_r: &aux.tree_r,
+        tree_r_config_rows_to_discard: default_rows_to_discard(nodes, BINARY_ARITY),
+    };
+    let proof_nc = DrgPoRep::<PoseidonHasher, _>::prove(&pp, &pub_inputs, &priv_inputs)
+        .expect("failed to prove");
+
+    let replica_parents = proof_nc
+        .replica_parents
+        .iter()
+        .map(|v| {
+            v.iter()
+                .map(|(_, parent)| Some(parent.data.into()))
+                .collect()
+        })
+        .collect();
+    let replica_parents_paths = proof_nc
+        .replica_parents
+        .iter()
+        .map(|v| {
+            v.iter()
+                .map(|(_, parent)| parent.proof.as_options())
+                .collect()
+        })
+        .collect();
+
+    let mut cs = TestConstraintSystem::<Bls12>::new();
+    DrgPoRepCircuit::<PoseidonHasher>::synthesize(
+        cs.namespace(|| "drgporep"),
+        vec![Some(proof_nc.replica_nodes[0].data.into())],
+        vec![proof_nc.replica_nodes[0].proof.as_options()],
+        Root::Val(Some(proof_nc.replica_root.into())),
+        replica_parents,
+        replica_parents_paths,
+        vec![Some(data_node)],
+        vec![proof_nc.nodes[0].proof.as_options()],
+        Root::Val(Some(proof_nc.data_root.into())),
+        Some(replica_id),
+        false,
+    )
+    .expect("failed to synthesize circuit");
+    assert!(cs.is_satisfied(), "constraints not satisfied");
+
+    // A data node which is not the decoding of the replica node under the parents' key must
+    // not satisfy the encoding check.
+    let mut corrupted = data_node;
+    corrupted.add_assign(&Fr::one());
+    cs.set(
+        "drgporep/challenge_0/encoding_checks/data node/num",
+        corrupted,
+    );
+    assert!(!cs.is_satisfied(), "corrupted node satisfied constraints");
+    assert_eq!(
+        cs.which_is_unsatisfied(),
+        Some("drgporep/challenge_0/encoding_checks/equality")
+    );
+
+    cache_dir.close().expect("Failed to remove cache dir");
+}
+
 #[test]
 fn test_drg_porep_circuit_inputs_and_constraints() {
     let rng = &mut XorShiftRng::from_seed(TEST_SEED);
@@ -171,6 +171,111 @@ fn test_extract<Tree: MerkleTreeTrait>() {
     }
 }
 
+#[test]
+fn test_drg_porep_verify_node_sha256() {
+    test_verify_node::<Sha256Hasher>();
+}
+
+#[test]
+fn test_drg_porep_verify_node_blake2s() {
+    test_verify_node::<Blake2sHasher>();
+}
+
+fn test_verify_node<H: 'static + Hasher>() {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    let replica_id: H::Domain = H::Domain::random(rng);
+    let nodes = 64;
+    let data: Vec<u8> = (0..nodes)
+        .flat_map(|_| fr_into_bytes(&Fr::random(&mut *rng)))
+        .collect();
+
+    let sp = drg::SetupParams {
+        drg: drg::DrgParams {
+            nodes,
+            degree: BASE_DEGREE,
+            expansion_degree: 0,
+            porep_id: [32; 32],
+        },
+        private: false,
+        challenges_count: 1,
+        api_version: ApiVersion::V1_1_0,
+    };
+    let pp = DrgPoRep::<H, BucketGraph<H>>::setup(&sp).expect("setup failed");
+
+    // Seal node by node, verifying each node as soon as it is written.
+    let mut replica = data.clone();
+    let mut parents = vec![0; BASE_DEGREE];
+    for node in 0..nodes {
+        drg::encode_node::<H, _>(&pp.graph, &replica_id, &mut replica, node, &mut parents)
+            .expect("encode_node failure");
+        let original_chunk = &data[node * 32..(node + 1) * 32];
+        assert!(
+            drg::verify_node::<H, _>(&replica, &pp.graph, &replica_id, node, original_chunk)
+                .expect("verify_node failure"),
+            "node {} failed to verify",
+            node
+        );
+    }
+
+    // Replace the encoding of a single node, which must be caught when verifying that node.
+    let corrupted = nodes / 2;
+    let garbage = fr_into_bytes(&Fr::random(rng));
+    replica[corrupted * 32..(corrupted + 1) * 32].copy_from_slice(&garbage);
+    let original_chunk = &data[corrupted * 32..(corrupted + 1) * 32];
+    assert!(
+        !drg::verify_node::<H, _>(&replica, &pp.graph, &replica_id, corrupted, original_chunk)
+            .expect("verify_node failure"),
+        "corrupted node verified"
+    );
+}
+
+#[test]
+fn test_drg_porep_extract_all_to_writer_sha256() {
+    test_extract_all_to_writer::<Sha256Hasher>();
+}
+
+#[test]
+fn test_drg_porep_extract_all_to_writer_blake2s() {
+    test_extract_all_to_writer::<Blake2sHasher>();
+}
+
+fn test_extract_all_to_writer<H: 'static + Hasher>() {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    let replica_id: H::Domain = H::Domain::random(rng);
+    let nodes = 1024;
+    let data: Vec<u8> = (0..nodes)
+        .flat_map(|_| fr_into_bytes(&Fr::random(&mut *rng)))
+        .collect();
+
+    let sp = drg::SetupParams {
+        drg: drg::DrgParams {
+            nodes,
+            degree: BASE_DEGREE,
+            expansion_degree: 0,
+            porep_id: [32; 32],
+        },
+        private: false,
+        challenges_count: 1,
+        api_version: ApiVersion::V1_1_0,
+    };
+    let pp = DrgPoRep::<H, BucketGraph<H>>::setup(&sp).expect("setup failed");
+
+    let mut replica = data.clone();
+    let mut parents = vec![0; BASE_DEGREE];
+    for node in 0..nodes {
+        drg::encode_node::<H, _>(&pp.graph, &replica_id, &mut replica, node, &mut parents)
+            .expect("encode_node failure");
+    }
+    assert_ne!(data, replica, "replication did not change data");
+
+    let mut extracted = Vec::new();
+    drg::extract_all_to_writer::<H, _, _>(&replica, &pp.graph, &replica_id, &mut extracted)
+        .expect("extract_all_to_writer failure");
+    assert_eq!(data, extracted, "failed to extract data");
+}
+
 table_tests! {
     test_prove_verify {
         test_drg_porep_prove_verify_32_16_1(16, 1);
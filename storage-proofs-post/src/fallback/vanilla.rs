@@ -157,6 +157,15 @@ impl<P: MerkleProofTrait> SectorProof<P> {
             .collect()
     }
 
+    /// Returns the challenged leaf index of every inclusion proof, as reconstructed from the
+    /// path.
+    pub fn challenged_indices(&self) -> Vec<usize> {
+        self.inclusion_proofs
+            .iter()
+            .map(MerkleProofTrait::path_index)
+            .collect()
+    }
+
     // Returns a read-only reference.
     pub fn inclusion_proofs(
         &self,
@@ -173,6 +182,78 @@ where
     _t: PhantomData<&'a Tree>,
 }
 
+impl<'a, Tree: 'a + MerkleTreeTrait> FallbackPoSt<'a, Tree> {
+    /// Proves all partitions like `prove_all_partitions`, but instead of failing when faulty
+    /// sectors are encountered, they are skipped and the proof only covers the healthy sectors.
+    ///
+    /// Leaf challenges depend on the position of a sector, so removing a faulty sector changes
+    /// the challenges of all sectors after it. Proving is therefore repeated until no new faulty
+    /// sectors are found.
+    ///
+    /// Returns the partition proofs together with the ids of the faulty sectors. The proofs
+    /// verify against `pub_inputs` with the faulty sectors removed.
+    pub fn prove_fault_tolerant(
+        pub_params: &PublicParams,
+        pub_inputs: &PublicInputs<<Tree::Hasher as Hasher>::Domain>,
+        priv_inputs: &PrivateInputs<'a, Tree>,
+        partition_count: usize,
+    ) -> Result<(Vec<Proof<Tree::Proof>>, Vec<SectorId>)> {
+        // Use `BTreeSet` so the faults are canonically ordered (sorted).
+        let mut faults = BTreeSet::new();
+
+        loop {
+            let (healthy_pub_sectors, healthy_priv_sectors): (Vec<_>, Vec<_>) = pub_inputs
+                .sectors
+                .iter()
+                .zip(priv_inputs.sectors.iter())
+                .filter(|(pub_sector, _)| !faults.contains(&pub_sector.id))
+                .map(|(pub_sector, priv_sector)| {
+                    (
+                        pub_sector.clone(),
+                        PrivateSector {
+                            tree: priv_sector.tree,
+                            comm_c: priv_sector.comm_c,
+                            comm_r_last: priv_sector.comm_r_last,
+                        },
+                    )
+                })
+                .unzip();
+            ensure!(
+                !healthy_pub_sectors.is_empty(),
+                Error::FaultySectors(faults.into_iter().collect())
+            );
+
+            let healthy_pub_inputs = PublicInputs {
+                randomness: pub_inputs.randomness,
+                prover_id: pub_inputs.prover_id,
+                sectors: healthy_pub_sectors,
+                k: pub_inputs.k,
+            };
+            let healthy_priv_inputs = PrivateInputs {
+                sectors: &healthy_priv_sectors,
+            };
+
+            match FallbackPoSt::<Tree>::prove_all_partitions(
+                pub_params,
+                &healthy_pub_inputs,
+                &healthy_priv_inputs,
+                partition_count,
+            ) {
+                Ok(proofs) => return Ok((proofs, faults.into_iter().collect())),
+                Err(err) => match err.downcast::<Error>() {
+                    // Faults are only reported for sectors which were part of this attempt, so
+                    // every round removes at least one sector.
+                    Ok(Error::FaultySectors(new_faults)) if !new_faults.is_empty() => {
+                        faults.extend(new_faults)
+                    }
+                    Ok(err) => return Err(err.into()),
+                    Err(err) => return Err(err),
+                },
+            }
+        }
+    }
+}
+
 pub fn generate_sector_challenges<T: Domain>(
     randomness: T,
     challenge_count: usize,
@@ -211,19 +292,23 @@ pub fn generate_leaf_challenges<T: Domain>(
     sector_id: u64,
     challenge_count: usize,
 ) -> Vec<u64> {
-    let mut challenges = Vec::with_capacity(challenge_count);
+    generate_leaf_challenges_iter(pub_params, randomness, sector_id, challenge_count).collect()
+}
 
+/// Lazily generates the same challenges as `generate_leaf_challenges`, in the same order.
+pub fn generate_leaf_challenges_iter<'a, T: Domain>(
+    pub_params: &'a PublicParams,
+    randomness: T,
+    sector_id: u64,
+    challenge_count: usize,
+) -> impl Iterator<Item = u64> + 'a {
     let mut hasher = Sha256::new();
     hasher.update(AsRef::<[u8]>::as_ref(&randomness));
     hasher.update(&sector_id.to_le_bytes()[..]);
 
-    for challenge_index in 0..challenge_count {
-        let challenge =
-            generate_leaf_challenge_inner::<T>(hasher.clone(), pub_params, challenge_index as u64);
-        challenges.push(challenge)
-    }
-
-    challenges
+    (0..challenge_count as u64).map(move |challenge_index| {
+        generate_leaf_challenge_inner::<T>(hasher.clone(), pub_params, challenge_index)
+    })
 }
 
 /// Generates challenge, such that the range fits into the sector.
@@ -253,6 +338,188 @@ pub fn generate_leaf_challenge_inner<T: Domain>(
     leaf_challenge % (pub_params.sector_size / NODE_SIZE as u64)
 }
 
+/// Distributes `total_challenges` leaf challenges over sectors proportionally to their size, so
+/// that every byte is challenged with the same probability. `sectors` holds the id and the size in
+/// bytes of each sector.
+///
+/// The challenged leaves of a sector are derived like in `generate_leaf_challenges`, only their
+/// number differs. Returns the challenged leaves for each sector, in the order of `sectors`.
+pub fn derive_weighted_challenges<T: Domain>(
+    randomness: T,
+    sectors: &[(SectorId, u64)],
+    total_challenges: usize,
+) -> Result<Vec<Vec<u64>>> {
+    ensure!(!sectors.is_empty(), "no sectors to challenge");
+    ensure!(
+        sectors
+            .iter()
+            .all(|(_, sector_size)| *sector_size > 0 && sector_size % NODE_SIZE as u64 == 0),
+        "sector sizes must be a non zero multiple of the node size"
+    );
+    let total_size: u128 = sectors
+        .iter()
+        .map(|(_, sector_size)| *sector_size as u128)
+        .sum();
+
+    // Largest remainder method, so that the counts add up to exactly `total_challenges`.
+    let quotas: Vec<(u128, u128)> = sectors
+        .iter()
+        .map(|(_, sector_size)| {
+            let share = total_challenges as u128 * *sector_size as u128;
+            (share / total_size, share % total_size)
+        })
+        .collect();
+    let mut counts: Vec<usize> = quotas.iter().map(|(count, _)| *count as usize).collect();
+    let assigned: usize = counts.iter().sum();
+
+    let mut by_remainder: Vec<usize> = (0..quotas.len()).collect();
+    by_remainder.sort_by(|a, b| quotas[*b].1.cmp(&quotas[*a].1).then(a.cmp(b)));
+    for i in by_remainder.into_iter().take(total_challenges - assigned) {
+        counts[i] += 1;
+    }
+
+    Ok(sectors
+        .iter()
+        .zip(counts)
+        .map(|((sector_id, sector_size), count)| {
+            let pub_params = PublicParams {
+                sector_size: *sector_size,
+                challenge_count: count,
+                sector_count: 1,
+                // Not used when deriving challenges.
+                api_version: ApiVersion::V1_1_0,
+            };
+            generate_leaf_challenges(&pub_params, randomness, (*sector_id).into(), count)
+        })
+        .collect())
+}
+
+/// Bits of soundness provided by `challenge_count` leaf challenges into a sector of `leaves` leaves,
+/// assuming the prover has corrupted at least `corrupted_fraction` of them. Each challenge misses
+/// the corrupted leaves with probability `1 - corrupted / leaves`.
+///
+/// `corrupted_fraction` must be in `(0, 1)`. If rounding up the corrupted leaves covers the whole
+/// sector, every challenge hits a corrupted leaf and the soundness is infinite.
+pub fn soundness_bits(challenge_count: usize, leaves: u64, corrupted_fraction: f64) -> Result<f64> {
+    let bits = challenge_soundness_bits(leaves, corrupted_fraction)?;
+    if challenge_count == 0 {
+        return Ok(0.);
+    }
+
+    Ok(challenge_count as f64 * bits)
+}
+
+/// The minimal number of leaf challenges providing `target_bits` of soundness, the inverse of
+/// `soundness_bits`. `target_bits` must be positive and `corrupted_fraction` must be in `(0, 1)`.
+pub fn challenges_for(target_bits: f64, leaves: u64, corrupted_fraction: f64) -> Result<usize> {
+    ensure!(
+        target_bits > 0.,
+        "target bits must be positive: {}",
+        target_bits
+    );
+    let challenges = target_bits / challenge_soundness_bits(leaves, corrupted_fraction)?;
+
+    // Don't round up exact multiples because of floating point errors.
+    let rounded = challenges.round();
+    let challenges = if (challenges - rounded).abs() < 1e-9 {
+        rounded as usize
+    } else {
+        challenges.ceil() as usize
+    };
+
+    // Infinite soundness per challenge still needs a single challenge.
+    Ok(challenges.max(1))
+}
+
+fn challenge_soundness_bits(leaves: u64, corrupted_fraction: f64) -> Result<f64> {
+    ensure!(leaves > 0, "no leaves to challenge");
+    ensure!(
+        corrupted_fraction > 0. && corrupted_fraction < 1.,
+        "corrupted fraction must be in (0, 1): {}",
+        corrupted_fraction
+    );
+
+    let leaves = leaves as f64;
+    let corrupted = (corrupted_fraction * leaves).ceil();
+
+    Ok(-(1. - corrupted / leaves).log2())
+}
+
+/// A challenged leaf together with its merkle path, see `ChallengeProofBuilder`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChallengeProof<H: Hasher> {
+    pub challenge: u64,
+    pub leaf: H::Domain,
+    /// The sibling hashes and the index of the path within them, for every level of the tree.
+    pub path: Vec<(Vec<H::Domain>, usize)>,
+}
+
+/// Derives the leaf challenges of a single sector and opens them in the sector's tree in one call.
+#[derive(Debug)]
+pub struct ChallengeProofBuilder<'a, Tree: MerkleTreeTrait> {
+    pub_params: &'a PublicParams,
+    tree: &'a Tree,
+    sector_id: SectorId,
+}
+
+impl<'a, Tree: MerkleTreeTrait> ChallengeProofBuilder<'a, Tree> {
+    pub fn new(pub_params: &'a PublicParams, tree: &'a Tree, sector_id: SectorId) -> Result<Self> {
+        ensure!(
+            tree.leaves() as u64 * NODE_SIZE as u64 == pub_params.sector_size,
+            "tree does not match the sector size"
+        );
+
+        Ok(ChallengeProofBuilder {
+            pub_params,
+            tree,
+            sector_id,
+        })
+    }
+
+    /// Builds the proofs for the leaf challenges of the sector, derived from `randomness`. The
+    /// challenges depend on the position of the sector in the PoSt, given by the index of its
+    /// partition and its index within that partition, like in `prove_all_partitions`.
+    pub fn build(
+        &self,
+        randomness: <Tree::Hasher as Hasher>::Domain,
+        partition_index: usize,
+        sector_index: usize,
+    ) -> Result<Vec<ChallengeProof<Tree::Hasher>>> {
+        ensure!(
+            sector_index < self.pub_params.sector_count,
+            "sector index out of bounds: {}",
+            sector_index
+        );
+
+        let rows_to_discard = default_rows_to_discard(self.tree.leaves(), Tree::Arity::to_usize());
+        let challenge_count = self.pub_params.challenge_count;
+
+        (0..challenge_count)
+            .map(|n| {
+                let challenge_index = ((partition_index * self.pub_params.sector_count
+                    + sector_index)
+                    * challenge_count
+                    + n) as u64;
+                let challenge = generate_leaf_challenge(
+                    self.pub_params,
+                    randomness,
+                    self.sector_id.into(),
+                    challenge_index,
+                );
+                let proof = self
+                    .tree
+                    .gen_cached_proof(challenge as usize, Some(rows_to_discard))?;
+
+                Ok(ChallengeProof {
+                    challenge,
+                    leaf: proof.leaf(),
+                    path: proof.path(),
+                })
+            })
+            .collect()
+    }
+}
+
 // Generates a single vanilla proof, given the private inputs and sector challenges.
 pub fn vanilla_proof<Tree: MerkleTreeTrait>(
     sector_id: SectorId,
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use bellperson::{
     bls::{Bls12, Fr},
     util_cs::{bench_cs::BenchCS, test_cs::TestConstraintSystem},
@@ -221,3 +223,67 @@ fn test_fallback_post_circuit_poseidon_base_8_bench_cs() {
 
     assert_eq!(cs.num_constraints(), 266_665);
 }
+
+/// Maximum relative deviation from the recorded constraint count baseline.
+const CONSTRAINT_BASELINE_TOLERANCE: f64 = 0.01;
+
+/// Guards against accidental circuit bloat. The baseline lives in
+/// `tests/fallback_constraint_baseline.json` and must be updated intentionally
+/// whenever the circuit is changed on purpose.
+#[test]
+fn test_fallback_post_circuit_constraint_baseline() {
+    let baseline: BTreeMap<String, usize> =
+        serde_json::from_str(include_str!("fallback_constraint_baseline.json"))
+            .expect("invalid constraint baseline file");
+
+    let actual = vec![
+        (
+            "window-post-2KiB",
+            blank_circuit_constraints::<LCTree<PoseidonHasher, U8, U0, U0>>(1 << 11, 10, 2),
+        ),
+        (
+            "winning-post-2KiB",
+            blank_circuit_constraints::<LCTree<PoseidonHasher, U8, U0, U0>>(1 << 11, 66, 1),
+        ),
+        (
+            "winning-post-32GiB",
+            blank_circuit_constraints::<LCTree<PoseidonHasher, U8, U8, U0>>(1 << 35, 66, 1),
+        ),
+    ];
+
+    for (name, num_constraints) in actual {
+        let expected = *baseline
+            .get(name)
+            .unwrap_or_else(|| panic!("missing constraint baseline for {}", name));
+        let deviation = (num_constraints as f64 - expected as f64).abs() / expected as f64;
+
+        assert!(
+            deviation <= CONSTRAINT_BASELINE_TOLERANCE,
+            "constraint count for {} deviates from baseline: {} != {}",
+            name,
+            num_constraints,
+            expected,
+        );
+    }
+}
+
+fn blank_circuit_constraints<Tree: 'static + MerkleTreeTrait>(
+    sector_size: u64,
+    challenge_count: usize,
+    sector_count: usize,
+) -> usize {
+    let params = fallback::SetupParams {
+        sector_size,
+        challenge_count,
+        sector_count,
+        api_version: ApiVersion::V1_1_0,
+    };
+    let pp = FallbackPoSt::<Tree>::setup(&params).expect("fallback post setup failure");
+
+    let mut cs = BenchCS::<Bls12>::new();
+    FallbackPoStCompound::<Tree>::blank_circuit(&pp)
+        .synthesize(&mut cs)
+        .expect("blank circuit failure");
+
+    cs.num_constraints()
+}
@@ -1,14 +1,20 @@
+use std::fs::write;
+
 use filecoin_hashers::{poseidon::PoseidonHasher, Domain, HashFunction, Hasher};
 use generic_array::typenum::{U0, U2, U4, U8};
+use merkletree::store::{ReplicaConfig, StoreConfig};
 use rand::SeedableRng;
 use rand_xorshift::XorShiftRng;
 use storage_proofs_core::{
     api_version::ApiVersion,
     error::Error,
-    merkle::{generate_tree, get_base_tree_count, LCTree, MerkleTreeTrait},
+    merkle::{
+        create_base_lcmerkle_tree, generate_tree, get_base_tree_count, LCTree, MerkleProofTrait,
+        MerkleTreeTrait,
+    },
     proof::ProofScheme,
     sector::SectorId,
-    util::NODE_SIZE,
+    util::{default_rows_to_discard, NODE_SIZE},
     TEST_SEED,
 };
 use storage_proofs_post::fallback::{self, FallbackPoSt, PrivateSector, PublicSector};
@@ -185,6 +191,97 @@ fn test_fallback_post<Tree: MerkleTreeTrait>(
     assert!(is_valid);
 }
 
+#[test]
+fn test_fallback_post_challenged_indices() {
+    type Tree = LCTree<PoseidonHasher, U8, U4, U0>;
+
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    let total_sector_count = 5;
+    let sector_count = 3;
+    let partitions = 2;
+    let leaves = 64 * get_base_tree_count::<Tree>();
+
+    let pub_params = fallback::PublicParams {
+        sector_size: (leaves * NODE_SIZE) as u64,
+        challenge_count: 10,
+        sector_count,
+        api_version: ApiVersion::V1_1_0,
+    };
+
+    let randomness = <PoseidonHasher as Hasher>::Domain::random(rng);
+    let prover_id = <PoseidonHasher as Hasher>::Domain::random(rng);
+
+    let temp_dir = tempdir().expect("tempdir failure");
+    let temp_path = temp_dir.path();
+
+    let trees = (0..total_sector_count)
+        .map(|_| generate_tree::<Tree, _>(rng, leaves, Some(temp_path.to_path_buf())).1)
+        .collect::<Vec<_>>();
+
+    let mut pub_sectors = Vec::new();
+    let mut priv_sectors = Vec::new();
+    for (i, tree) in trees.iter().enumerate() {
+        let comm_c = <PoseidonHasher as Hasher>::Domain::random(rng);
+        let comm_r_last = tree.root();
+
+        priv_sectors.push(PrivateSector {
+            tree,
+            comm_c,
+            comm_r_last,
+        });
+        pub_sectors.push(PublicSector {
+            id: (i as u64 * 7).into(),
+            comm_r: <PoseidonHasher as Hasher>::Function::hash2(&comm_c, &comm_r_last),
+        });
+    }
+
+    let pub_inputs = fallback::PublicInputs {
+        randomness,
+        prover_id,
+        sectors: pub_sectors,
+        k: None,
+    };
+    let priv_inputs = fallback::PrivateInputs::<Tree> {
+        sectors: &priv_sectors[..],
+    };
+
+    let proof = FallbackPoSt::<Tree>::prove_all_partitions(
+        &pub_params,
+        &pub_inputs,
+        &priv_inputs,
+        partitions,
+    )
+    .expect("proving failed");
+
+    // The leaves proven by each sector proof match the challenge derivation.
+    for (j, (partition_proof, partition_sectors)) in proof
+        .iter()
+        .zip(pub_inputs.sectors.chunks(sector_count))
+        .enumerate()
+    {
+        for (i, (pub_sector, sector_proof)) in partition_sectors
+            .iter()
+            .zip(partition_proof.sectors.iter())
+            .enumerate()
+        {
+            let expected: Vec<usize> = (0..pub_params.challenge_count)
+                .map(|n| {
+                    let challenge_index =
+                        ((j * sector_count + i) * pub_params.challenge_count + n) as u64;
+                    fallback::generate_leaf_challenge(
+                        &pub_params,
+                        randomness,
+                        pub_sector.id.into(),
+                        challenge_index,
+                    ) as usize
+                })
+                .collect();
+            assert_eq!(sector_proof.challenged_indices(), expected);
+        }
+    }
+}
+
 #[test]
 fn test_invalid_fallback_post_poseidon_single_partition_base_8() {
     test_invalid_fallback_post::<LCTree<PoseidonHasher, U8, U0, U0>>(5, 5, 1, ApiVersion::V1_0_0);
@@ -379,3 +476,463 @@ fn test_invalid_fallback_post<Tree: MerkleTreeTrait>(
         },
     };
 }
+
+#[test]
+fn test_fault_tolerant_fallback_post_poseidon_base_8() {
+    test_fault_tolerant_fallback_post::<LCTree<PoseidonHasher, U8, U0, U0>>(5, 5, 1);
+    test_fault_tolerant_fallback_post::<LCTree<PoseidonHasher, U8, U0, U0>>(5, 3, 2);
+}
+
+#[test]
+fn test_fault_tolerant_fallback_post_poseidon_top_8_4_2() {
+    test_fault_tolerant_fallback_post::<LCTree<PoseidonHasher, U8, U4, U2>>(5, 5, 1);
+}
+
+fn test_fault_tolerant_fallback_post<Tree: 'static + MerkleTreeTrait>(
+    total_sector_count: usize,
+    sector_count: usize,
+    partitions: usize,
+) where
+    Tree::Store: 'static,
+{
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    let leaves = 64 * get_base_tree_count::<Tree>();
+    let sector_size = leaves * NODE_SIZE;
+
+    let pub_params = fallback::PublicParams {
+        sector_size: sector_size as u64,
+        challenge_count: 10,
+        sector_count,
+        api_version: ApiVersion::V1_1_0,
+    };
+
+    let randomness = <Tree::Hasher as Hasher>::Domain::random(rng);
+    let prover_id = <Tree::Hasher as Hasher>::Domain::random(rng);
+
+    let temp_dir = tempdir().expect("tempdir failure");
+    let temp_path = temp_dir.path();
+
+    let trees: Vec<_> = (0..total_sector_count)
+        .map(|_| generate_tree::<Tree, _>(rng, leaves, Some(temp_path.to_path_buf())).1)
+        .collect();
+    let (_data, wrong_tree) = generate_tree::<Tree, _>(rng, leaves, Some(temp_path.to_path_buf()));
+
+    // The data of this sector does not match its commitment anymore.
+    let faulty_sector = 1;
+
+    let mut pub_sectors = Vec::new();
+    let mut priv_sectors = Vec::new();
+    for (i, tree) in trees.iter().enumerate() {
+        let comm_c = <Tree::Hasher as Hasher>::Domain::random(rng);
+        let comm_r_last = tree.root();
+
+        priv_sectors.push(PrivateSector {
+            tree: if i == faulty_sector {
+                &wrong_tree
+            } else {
+                tree
+            },
+            comm_c,
+            comm_r_last,
+        });
+        pub_sectors.push(PublicSector {
+            id: (i as u64).into(),
+            comm_r: <Tree::Hasher as Hasher>::Function::hash2(&comm_c, &comm_r_last),
+        });
+    }
+
+    let pub_inputs = fallback::PublicInputs {
+        randomness,
+        prover_id,
+        sectors: pub_sectors,
+        k: None,
+    };
+    let priv_inputs = fallback::PrivateInputs::<Tree> {
+        sectors: &priv_sectors[..],
+    };
+
+    let (proofs, faults) = FallbackPoSt::<Tree>::prove_fault_tolerant(
+        &pub_params,
+        &pub_inputs,
+        &priv_inputs,
+        partitions,
+    )
+    .expect("proving failed");
+    assert_eq!(faults, vec![SectorId::from(faulty_sector as u64)]);
+
+    let healthy_pub_inputs = fallback::PublicInputs {
+        sectors: pub_inputs
+            .sectors
+            .iter()
+            .filter(|sector| !faults.contains(&sector.id))
+            .cloned()
+            .collect(),
+        ..pub_inputs.clone()
+    };
+    let is_valid =
+        FallbackPoSt::<Tree>::verify_all_partitions(&pub_params, &healthy_pub_inputs, &proofs)
+            .expect("verification failed");
+    assert!(is_valid, "proof of the healthy sectors is invalid");
+}
+
+#[test]
+fn test_fault_tolerant_fallback_post_partially_corrupted() {
+    type Tree = LCTree<PoseidonHasher, U8, U0, U0>;
+
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    let leaves = 4096;
+    let sector_count = 5;
+    let pub_params = fallback::PublicParams {
+        sector_size: (leaves * NODE_SIZE) as u64,
+        challenge_count: 2,
+        sector_count,
+        api_version: ApiVersion::V1_1_0,
+    };
+
+    let randomness = <PoseidonHasher as Hasher>::Domain::random(rng);
+    let prover_id = <PoseidonHasher as Hasher>::Domain::random(rng);
+
+    let temp_dir = tempdir().expect("tempdir failure");
+    let temp_path = temp_dir.path();
+
+    let trees: Vec<_> = (0..sector_count)
+        .map(|_| generate_tree::<Tree, _>(rng, leaves, Some(temp_path.to_path_buf())).1)
+        .collect();
+    let (_data, wrong_tree) = generate_tree::<Tree, _>(rng, leaves, Some(temp_path.to_path_buf()));
+
+    // The tree of the partially corrupted sector commits to all of its data, but the second half
+    // of the leaves is missing from its replica, so no proofs can be generated for them. (A
+    // replica with different leaves instead would fail the debug assertions on proof validity.)
+    let data: Vec<u8> = (0..leaves)
+        .flat_map(|_| <PoseidonHasher as Hasher>::Domain::random(rng).into_bytes())
+        .collect();
+    let replica_path = temp_path.join("partially-corrupted-replica");
+    write(&replica_path, &data[..leaves / 2 * NODE_SIZE]).expect("failed to write replica");
+    let rows_to_discard = default_rows_to_discard(leaves, 8);
+    let corrupted_tree = create_base_lcmerkle_tree::<PoseidonHasher, U8>(
+        StoreConfig::new(temp_path, "partially-corrupted-tree", rows_to_discard),
+        leaves,
+        &data,
+        &ReplicaConfig::new(&replica_path, vec![0]),
+    )
+    .expect("failed to create partially corrupted tree");
+
+    // Sector 0 is completely faulty. Once it is dropped, all other sectors move one position
+    // forward and get different challenges. The partially corrupted sector is at position 1, and
+    // its id is chosen such that only its challenges at position 0 hit the missing leaves.
+    let corrupted_position = 1;
+    let is_faulty_at = |sector_id: u64, position: usize| {
+        (0..pub_params.challenge_count).any(|n| {
+            let challenge_index = (position * pub_params.challenge_count + n) as u64;
+            let challenge = fallback::generate_leaf_challenge(
+                &pub_params,
+                randomness,
+                sector_id,
+                challenge_index,
+            );
+            corrupted_tree
+                .gen_cached_proof(challenge as usize, Some(rows_to_discard))
+                .is_err()
+        })
+    };
+    let corrupted_id = (sector_count as u64..)
+        .find(|id| {
+            !is_faulty_at(*id, corrupted_position) && is_faulty_at(*id, corrupted_position - 1)
+        })
+        .expect("no sector id is only faulty after moving forward");
+
+    let mut pub_sectors = Vec::new();
+    let mut priv_sectors = Vec::new();
+    for (i, tree) in trees.iter().enumerate() {
+        let comm_c = <PoseidonHasher as Hasher>::Domain::random(rng);
+        let (tree, comm_r_last, id) = match i {
+            0 => (&wrong_tree, tree.root(), 0),
+            _ if i == corrupted_position => (&corrupted_tree, corrupted_tree.root(), corrupted_id),
+            _ => (tree, tree.root(), i as u64),
+        };
+
+        priv_sectors.push(PrivateSector {
+            tree,
+            comm_c,
+            comm_r_last,
+        });
+        pub_sectors.push(PublicSector {
+            id: id.into(),
+            comm_r: <PoseidonHasher as Hasher>::Function::hash2(&comm_c, &comm_r_last),
+        });
+    }
+
+    let pub_inputs = fallback::PublicInputs {
+        randomness,
+        prover_id,
+        sectors: pub_sectors,
+        k: None,
+    };
+    let priv_inputs = fallback::PrivateInputs::<Tree> {
+        sectors: &priv_sectors[..],
+    };
+
+    // A single attempt only finds the completely faulty sector.
+    match FallbackPoSt::<Tree>::prove_all_partitions(&pub_params, &pub_inputs, &priv_inputs, 1)
+        .expect_err("proving succeeded with faulty sectors")
+        .downcast::<Error>()
+    {
+        Ok(Error::FaultySectors(sector_ids)) => assert_eq!(sector_ids, vec![SectorId::from(0)]),
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    let (proofs, faults) =
+        FallbackPoSt::<Tree>::prove_fault_tolerant(&pub_params, &pub_inputs, &priv_inputs, 1)
+            .expect("proving failed");
+    assert_eq!(
+        faults,
+        vec![SectorId::from(0), SectorId::from(corrupted_id)]
+    );
+
+    let healthy_pub_inputs = fallback::PublicInputs {
+        sectors: pub_inputs
+            .sectors
+            .iter()
+            .filter(|sector| !faults.contains(&sector.id))
+            .cloned()
+            .collect(),
+        ..pub_inputs.clone()
+    };
+    let is_valid =
+        FallbackPoSt::<Tree>::verify_all_partitions(&pub_params, &healthy_pub_inputs, &proofs)
+            .expect("verification failed");
+    assert!(is_valid, "proof of the healthy sectors is invalid");
+}
+
+#[test]
+fn test_generate_leaf_challenges_iter() {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    let pub_params = fallback::PublicParams {
+        sector_size: 1024 * 1024,
+        challenge_count: 66,
+        sector_count: 1,
+        api_version: ApiVersion::V1_1_0,
+    };
+    let randomness = <PoseidonHasher as Hasher>::Domain::random(rng);
+
+    for sector_id in 0..10 {
+        let challenges = fallback::generate_leaf_challenges(
+            &pub_params,
+            randomness,
+            sector_id,
+            pub_params.challenge_count,
+        );
+        let iter_challenges: Vec<_> = fallback::generate_leaf_challenges_iter(
+            &pub_params,
+            randomness,
+            sector_id,
+            pub_params.challenge_count,
+        )
+        .collect();
+        assert_eq!(challenges, iter_challenges);
+
+        for (i, challenge) in iter_challenges.iter().enumerate() {
+            assert_eq!(
+                *challenge,
+                fallback::generate_leaf_challenge(&pub_params, randomness, sector_id, i as u64)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_soundness_bits() {
+    let bits = |challenge_count, leaves, corrupted_fraction| {
+        fallback::soundness_bits(challenge_count, leaves, corrupted_fraction)
+            .expect("soundness_bits failure")
+    };
+    let challenges_for = |target_bits, leaves, corrupted_fraction| {
+        fallback::challenges_for(target_bits, leaves, corrupted_fraction)
+            .expect("challenges_for failure")
+    };
+
+    // Half of two leaves corrupted, every challenge is a coin flip.
+    assert!((bits(1, 2, 0.5) - 1.).abs() < 1e-9);
+    assert!((bits(10, 2, 0.5) - 10.).abs() < 1e-9);
+
+    for sector_size in &[2048u64, 8 * 1024 * 1024, 512 * 1024 * 1024, 32 << 30] {
+        let leaves = sector_size / NODE_SIZE as u64;
+        for corrupted_fraction in &[0.01, 0.1, 0.2, 0.5] {
+            for challenge_count in &[1, 10, 66, 100, 1000] {
+                let target_bits = bits(*challenge_count, leaves, *corrupted_fraction);
+                assert_eq!(
+                    challenges_for(target_bits, leaves, *corrupted_fraction),
+                    *challenge_count
+                );
+            }
+        }
+    }
+
+    // Fractional targets need one more challenge.
+    assert_eq!(challenges_for(bits(10, 2, 0.5) + 0.5, 2, 0.5), 11);
+
+    // All leaves are corrupted once rounded up, a single challenge is enough.
+    assert!(bits(1, 2, 0.6).is_infinite());
+    assert_eq!(bits(0, 2, 0.6), 0.);
+    assert_eq!(challenges_for(128., 2, 0.6), 1);
+
+    for corrupted_fraction in &[-0.5, 0., 1., 1.5, f64::NAN] {
+        assert!(fallback::soundness_bits(10, 1024, *corrupted_fraction).is_err());
+        assert!(fallback::challenges_for(10., 1024, *corrupted_fraction).is_err());
+    }
+    for target_bits in &[-1., 0., f64::NAN] {
+        assert!(fallback::challenges_for(*target_bits, 1024, 0.5).is_err());
+    }
+    assert!(fallback::soundness_bits(10, 0, 0.5).is_err());
+}
+
+#[test]
+fn test_derive_weighted_challenges() {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+    let randomness = <PoseidonHasher as Hasher>::Domain::random(rng);
+
+    let sectors = [
+        (SectorId::from(3), 1024 * NODE_SIZE as u64),
+        (SectorId::from(9), 3072 * NODE_SIZE as u64),
+    ];
+    let challenges = fallback::derive_weighted_challenges(randomness, &sectors, 100)
+        .expect("failed to derive challenges");
+    assert_eq!(challenges[0].len(), 25);
+    assert_eq!(challenges[1].len(), 75);
+
+    // The challenged leaves are tied to the sector id, not to the position of the sector.
+    for ((sector_id, sector_size), sector_challenges) in sectors.iter().zip(&challenges) {
+        let pub_params = fallback::PublicParams {
+            sector_size: *sector_size,
+            challenge_count: sector_challenges.len(),
+            sector_count: 1,
+            api_version: ApiVersion::V1_1_0,
+        };
+        assert_eq!(
+            sector_challenges,
+            &fallback::generate_leaf_challenges(
+                &pub_params,
+                randomness,
+                (*sector_id).into(),
+                sector_challenges.len(),
+            )
+        );
+    }
+    let swapped = fallback::derive_weighted_challenges(randomness, &[sectors[1], sectors[0]], 100)
+        .expect("failed to derive challenges");
+    assert_eq!(swapped, vec![challenges[1].clone(), challenges[0].clone()]);
+
+    // Counts which don't divide evenly still add up.
+    let sectors: Vec<_> = [7u64, 5, 3, 1]
+        .iter()
+        .enumerate()
+        .map(|(i, leaves)| (SectorId::from(i as u64), leaves * NODE_SIZE as u64))
+        .collect();
+    for total in 0..50 {
+        let challenges = fallback::derive_weighted_challenges(randomness, &sectors, total)
+            .expect("failed to derive challenges");
+        assert_eq!(challenges.iter().map(Vec::len).sum::<usize>(), total);
+    }
+
+    assert!(fallback::derive_weighted_challenges(randomness, &[], 10).is_err());
+    assert!(fallback::derive_weighted_challenges(
+        randomness,
+        &[(SectorId::from(0), 1024), (SectorId::from(1), 0)],
+        10
+    )
+    .is_err());
+}
+
+#[test]
+fn test_challenge_proof_builder() {
+    type Tree = LCTree<PoseidonHasher, U8, U0, U0>;
+
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    let leaves = 64;
+    let sector_count = 3;
+    let pub_params = fallback::PublicParams {
+        sector_size: (leaves * NODE_SIZE) as u64,
+        challenge_count: 10,
+        sector_count,
+        api_version: ApiVersion::V1_1_0,
+    };
+    let randomness = <PoseidonHasher as Hasher>::Domain::random(rng);
+    let prover_id = <PoseidonHasher as Hasher>::Domain::random(rng);
+
+    let temp_dir = tempdir().expect("tempdir failure");
+    let trees: Vec<_> = (0..5)
+        .map(|_| generate_tree::<Tree, _>(rng, leaves, Some(temp_dir.path().to_path_buf())).1)
+        .collect();
+
+    let mut pub_sectors = Vec::new();
+    let mut priv_sectors = Vec::new();
+    for (i, tree) in trees.iter().enumerate() {
+        let comm_c = <PoseidonHasher as Hasher>::Domain::random(rng);
+        let comm_r_last = tree.root();
+
+        priv_sectors.push(PrivateSector {
+            tree,
+            comm_c,
+            comm_r_last,
+        });
+        pub_sectors.push(PublicSector {
+            id: (i as u64 * 7).into(),
+            comm_r: <PoseidonHasher as Hasher>::Function::hash2(&comm_c, &comm_r_last),
+        });
+    }
+
+    let pub_inputs = fallback::PublicInputs {
+        randomness,
+        prover_id,
+        sectors: pub_sectors,
+        k: None,
+    };
+    let priv_inputs = fallback::PrivateInputs::<Tree> {
+        sectors: &priv_sectors[..],
+    };
+    let partition_proofs =
+        FallbackPoSt::<Tree>::prove_all_partitions(&pub_params, &pub_inputs, &priv_inputs, 2)
+            .expect("proving failed");
+
+    // The builder opens the same leaves as the sector proofs of the PoSt.
+    for (j, (pub_sectors, trees)) in pub_inputs
+        .sectors
+        .chunks(sector_count)
+        .zip(trees.chunks(sector_count))
+        .enumerate()
+    {
+        for (i, (pub_sector, tree)) in pub_sectors.iter().zip(trees).enumerate() {
+            let builder = fallback::ChallengeProofBuilder::new(&pub_params, tree, pub_sector.id)
+                .expect("failed to create builder");
+            let proofs = builder
+                .build(randomness, j, i)
+                .expect("failed to build proofs");
+
+            let inclusion_proofs = partition_proofs[j].sectors[i].inclusion_proofs();
+            assert_eq!(proofs.len(), inclusion_proofs.len());
+            for (proof, expected) in proofs.iter().zip(inclusion_proofs) {
+                assert_eq!(proof.challenge, expected.path_index() as u64);
+                assert_eq!(proof.leaf, expected.leaf());
+                assert_eq!(proof.path, expected.path());
+            }
+        }
+    }
+
+    let builder =
+        fallback::ChallengeProofBuilder::new(&pub_params, &trees[0], pub_inputs.sectors[0].id)
+            .expect("failed to create builder");
+    assert!(builder.build(randomness, 0, sector_count).is_err());
+
+    let wrong_size = fallback::PublicParams {
+        sector_size: pub_params.sector_size * 2,
+        ..pub_params.clone()
+    };
+    assert!(
+        fallback::ChallengeProofBuilder::new(&wrong_size, &trees[0], pub_inputs.sectors[0].id)
+            .is_err()
+    );
+}